@@ -3,34 +3,274 @@ use hex::encode;
 
 use soroban_sdk::xdr::ToXdr;
 use soroban_sdk::{
-    contract, contractimpl, panic_with_error, token, Address, Bytes, BytesN, Env, String,
+    contract, contractimpl, panic_with_error, symbol_short, token, Address, Bytes, BytesN, Env,
+    String, Symbol, Vec,
 };
 
-use crate::errors::ContractErrors;
+use crate::errors::{
+    ContractErrors, DelegationErrors, GovernanceErrors, GuardianErrors, SweepErrors,
+};
 use crate::storage::{
-    delete_stake, get_attempt, get_block, get_miner_attempt, get_miner_attempt_index, get_stake,
-    get_state, pump_block, pump_core, pump_stake, set_attempt, set_block, set_miner_attempt,
-    set_miner_attempt_index, set_stake, set_state, Attempt, Block, MinerAttempt, ReactorState,
-    Stake,
+    delete_armed_nuke, delete_emission_breaker, delete_hot_rewards, delete_pending_admin,
+    delete_pending_upgrade, delete_ve_lock, get_admin, get_allowlist_enabled, get_armed_nuke,
+    get_asset_stake_lots, get_asset_staked, get_attempt, get_auto_compound, get_block, get_boost,
+    get_cooling_period, get_deferred_rewards, get_delegated_in, get_delegated_out, get_delegation,
+    get_delegators, get_emission_breaker, get_guardians, get_held_fcm, get_hot_rewards,
+    get_last_proposal, get_miner_attempt, get_miner_attempt_index, get_owner_assets, get_paused,
+    get_pending_admin, get_pending_upgrade, get_prize_pool, get_prize_pool_config, get_proposal,
+    get_proposal_count, get_proposal_vote, get_receipt_deposit, get_receipt_deposits,
+    get_receipt_token, get_receipted_stake, get_requirement_curve, get_revenue_share,
+    get_reward_per_token, get_schema_version, get_stake_assets, get_stake_lots, get_staker_rewards,
+    get_staking_cut, get_state, get_total_minted, get_total_staked, get_ve_lock,
+    get_voting_checkpoints, get_voting_totals, is_allowed_miner, is_denied_miner, is_role_member,
+    pump_asset_stake_lots, pump_asset_staked, pump_auto_compound, pump_block, pump_boost,
+    pump_core, pump_deferred_rewards, pump_delegated, pump_delegation, pump_hot_rewards,
+    pump_miner_list_entry, pump_owner_assets, pump_prize_pool, pump_proposal, pump_receipted_stake,
+    pump_stake, pump_staker_rewards, pump_ve_lock, set_admin, set_allowed_miner,
+    set_allowlist_enabled, set_armed_nuke, set_asset_stake_lots, set_asset_staked, set_attempt,
+    set_auto_compound, set_block, set_boost, set_cooling_period, set_deferred_rewards,
+    set_delegated_in, set_delegated_out, set_delegation, set_delegators, set_denied_miner,
+    set_emission_breaker, set_guardians, set_held_fcm, set_hot_rewards, set_last_proposal,
+    set_miner_attempt, set_miner_attempt_index, set_paused, set_pending_admin, set_pending_upgrade,
+    set_prize_pool, set_prize_pool_config, set_proposal, set_proposal_count, set_proposal_vote,
+    set_receipt_deposit, set_receipt_deposits, set_receipt_token, set_receipted_stake,
+    set_requirement_curve, set_revenue_share, set_reward_per_token, set_role_member,
+    set_schema_version, set_stake_assets, set_stake_lots, set_staker_rewards, set_staking_cut,
+    set_state, set_total_minted, set_total_staked, set_ve_lock, set_voting_checkpoints,
+    set_voting_totals, ArmedNuke, Attempt, Block, Boost, CurveBasis, CurveKind, EmissionBreaker,
+    GuardianSet, HotRewards, MinerAttempt, PendingUpgrade, PrizePoolConfig, Proposal,
+    ProposalAction, ReactorConfig, ReactorState, RequirementCurve, Role, StakeAsset, StakeLot,
+    StakerRewards, StorageKeys, UnstakeQuote, VeLock, VotingCheckpoint, VotingTotals,
+    SCHEMA_VERSION,
 };
 
 pub const MAX_SUPPLY: u64 = 16_000_000u64;
 pub const STAKING_DIVISOR: u64 = 10_000u64;
+pub const MAX_PRIZE_POOL_TOKENS: u32 = 5;
+pub const MAX_PRIZE_DRIP_BPS: u32 = 10_000;
+pub const MAX_ATTEMPT_TICKETS: u32 = 255;
+pub const BOOST_UNIT: u128 = 1_0000000u128;
+pub const BOOST_DURATION: u64 = 1_440u64;
+pub const MAX_BOOST_WEIGHT: u32 = 4;
+pub const STAKE_COOLDOWN: u64 = 3600 * 24 * 60;
+pub const MAX_STAKE_LOTS: u32 = 20;
+pub const VE_MIN_LOCK: u64 = STAKE_COOLDOWN;
+pub const VE_MAX_LOCK: u64 = 3600 * 24 * 730;
+pub const VE_TICKET_STAKE: u128 = 100_0000000u128;
+pub const VE_MAX_BONUS_TICKETS: u32 = 2;
+pub const MAX_COOLING_PERIOD: u64 = STAKE_COOLDOWN;
+pub const MIN_EMISSION_WINDOW: u64 = 3600;
+pub const MAX_EMISSION_WINDOW: u64 = 3600 * 24 * 30;
+pub const EMISSION_BUCKETS: u32 = 4;
+pub const MAX_DELEGATORS: u32 = 10;
+pub const MIN_DELEGATION: u128 = 1_0000000u128;
+pub const MAX_REVENUE_SHARE_BPS: u32 = 10_000;
+pub const MAX_STAKING_CUT_BPS: u32 = 5_000;
+pub const REWARD_PER_TOKEN_PRECISION: u128 = 1_000_000_000_000u128;
+pub const MAX_STAKE_ASSETS: u32 = 5;
+pub const STAKE_ASSET_WEIGHT_UNIT: u128 = 1_0000000u128;
+pub const MAX_REQUIREMENT_STEP_AMOUNT: u128 = 100_0000000u128;
+pub const MAX_REQUIREMENT_GROWTH_BPS: u32 = 10_000;
+pub const MAX_STAKE_REQUIREMENT: u128 = MAX_SUPPLY as u128 * 1_0000000u128;
+pub const GROWTH_PRECISION: u128 = 1_000_000_000u128;
+pub const UPGRADE_DELAY: u64 = 3600 * 24 * 7;
+pub const VOTING_PERIOD: u32 = 17280 * 7;
+pub const PROPOSAL_EXECUTION_DELAY: u32 = 17280 * 2;
+pub const MIN_PROPOSAL_POWER: u128 = 1_0000000;
+pub const PROPOSAL_QUORUM_BPS: u128 = 2_000;
+pub const PROPOSER_COOLDOWN: u32 = VOTING_PERIOD;
+pub const VOTING_LOCK_WEEK: u64 = 3600 * 24 * 7;
+pub const MAX_GUARDIANS: u32 = 10;
+pub const NUKE_GRACE_PERIOD: u64 = 3600 * 24 * 3;
 
 pub trait ReactorContractTrait {
-    fn upgrade(e: Env, hash: BytesN<32>);
+    fn schedule_upgrade(e: Env, caller: Address, hash: BytesN<32>);
+
+    fn execute_upgrade(e: Env, caller: Address);
+
+    fn cancel_upgrade(e: Env, caller: Address);
+
+    fn pending_upgrade(e: Env) -> Option<PendingUpgrade>;
+
+    fn set_difficulty(e: &Env, caller: Address, difficulty: u32);
+
+    fn migrate(e: Env, caller: Address);
+
+    fn schema_version(e: Env) -> u32;
+
+    fn admin(e: Env) -> Address;
 
-    fn set_difficulty(e: &Env, difficulty: u32);
+    fn propose_admin(e: Env, new_admin: Address);
 
-    fn find(e: Env, fcm: Address, miner: Address, message: String);
+    fn accept_admin(e: Env);
+
+    fn pending_admin(e: Env) -> Option<Address>;
+
+    fn grant_role(e: Env, role: Role, account: Address);
+
+    fn revoke_role(e: Env, role: Role, account: Address);
+
+    fn has_role(e: Env, role: Role, account: Address) -> bool;
+
+    fn initialize(e: Env, admin: Address, salt: BytesN<32>, config: ReactorConfig);
 
     fn mine(e: Env, hash: BytesN<32>, message: String, nonce: u64, miner: Address);
 
     fn stake(e: Env, caller: Address, amount: u128);
 
-    fn un_stake(e: Env, caller: Address);
+    fn un_stake(e: Env, caller: Address, amount: u128);
+
+    fn stake_lots(e: Env, miner: Address) -> Vec<StakeLot>;
+
+    fn bump_stake(e: Env, miner: Address);
+
+    fn emergency_unstake(e: Env, caller: Address) -> UnstakeQuote;
+
+    fn emergency_unstake_quote(e: Env, miner: Address) -> UnstakeQuote;
+
+    fn set_stake_assets(e: Env, assets: Vec<StakeAsset>);
+
+    fn stake_assets(e: Env) -> Vec<StakeAsset>;
+
+    fn stake_asset(e: Env, caller: Address, token: Address, amount: u128);
+
+    fn un_stake_asset(e: Env, caller: Address, token: Address, amount: u128);
+
+    fn asset_stake_lots(e: Env, miner: Address, token: Address) -> Vec<StakeLot>;
+
+    fn ve_lock(e: Env, owner: Address, amount: u128, duration: u64);
+
+    fn ve_increase(e: Env, owner: Address, amount: u128);
+
+    fn ve_extend(e: Env, owner: Address, duration: u64);
+
+    fn ve_withdraw(e: Env, owner: Address);
+
+    fn ve_lock_of(e: Env, owner: Address) -> Option<VeLock>;
+
+    fn effective_stake(e: Env, miner: Address) -> u128;
+
+    fn set_requirement_curve(e: Env, curve: RequirementCurve);
+
+    fn requirement_curve(e: Env) -> RequirementCurve;
+
+    fn stake_requirement(e: Env, at: u64) -> u128;
+
+    fn total_minted(e: Env) -> u128;
+
+    fn set_receipt_token(e: Env, token: Address);
+
+    fn receipt_token(e: Env) -> Option<Address>;
+
+    fn receipted_stake(e: Env, miner: Address) -> u128;
+
+    fn deposit_receipt(e: Env, holder: Address, amount: u128);
+
+    fn withdraw_receipt(e: Env, holder: Address, amount: u128);
+
+    fn receipt_deposit(e: Env, holder: Address) -> u128;
+
+    fn delegate_stake(e: Env, owner: Address, miner: Address, amount: u128);
+
+    fn revoke_delegation(e: Env, owner: Address, miner: Address);
+
+    fn delegation(e: Env, owner: Address, miner: Address) -> u128;
+
+    fn delegators(e: Env, miner: Address) -> Vec<Address>;
+
+    fn set_revenue_share(e: Env, miner: Address, share_bps: u32);
+
+    fn revenue_share(e: Env, miner: Address) -> u32;
+
+    fn set_guardians(e: Env, guardians: Vec<Address>, members: Vec<Address>, threshold: u32);
+
+    fn guardians(e: Env) -> Option<GuardianSet>;
+
+    fn fkin_nuke_it(e: Env, guardians: Vec<Address>);
+
+    fn cancel_nuke(e: Env, guardians: Vec<Address>);
+
+    fn detonate_nuke(e: Env);
+
+    fn armed_nuke(e: Env) -> Option<ArmedNuke>;
+
+    fn pause(e: Env, caller: Address);
+
+    fn unpause(e: Env, caller: Address);
+
+    fn is_paused(e: Env) -> bool;
+
+    fn is_terminal(e: Env) -> bool;
+
+    fn sweep(e: Env, token: Address, to: Address, amount: u128);
+
+    fn sweepable(e: Env, token: Address) -> u128;
+
+    fn set_denied(e: Env, miner: Address, denied: bool);
+
+    fn is_denied(e: Env, miner: Address) -> bool;
+
+    fn set_allowed(e: Env, miner: Address, allowed: bool);
+
+    fn is_allowed(e: Env, miner: Address) -> bool;
+
+    fn set_allowlist_enabled(e: Env, enabled: bool);
+
+    fn allowlist_enabled(e: Env) -> bool;
+
+    fn propose(e: Env, proposer: Address, action: ProposalAction) -> u64;
+
+    fn vote(e: Env, voter: Address, id: u64, support: bool);
 
-    fn fkin_nuke_it(e: Env, caller: Address);
+    fn execute_proposal(e: Env, id: u64);
+
+    fn proposal(e: Env, id: u64) -> Option<Proposal>;
+
+    fn voting_power(e: Env, account: Address) -> u128;
+
+    fn voting_power_at(e: Env, account: Address, ledger: u32) -> u128;
+
+    fn set_prize_pool(e: Env, tokens: Vec<Address>, drip_bps: u32);
+
+    fn deposit_prize(e: Env, caller: Address, token: Address, amount: u128);
+
+    fn withdraw_prize(e: Env, token: Address, to: Address, amount: u128);
+
+    fn prize_pool(e: Env, token: Address) -> u128;
+
+    fn prize_pool_drip(e: Env, token: Address) -> u128;
+
+    fn burn_for_boost(e: Env, miner: Address, amount: u128);
+
+    fn boost(e: Env, miner: Address) -> Option<Boost>;
+
+    fn set_cooling_period(e: Env, period: u64);
+
+    fn withdraw_cooled(e: Env, miner: Address);
+
+    fn hot_rewards(e: Env, miner: Address) -> Option<HotRewards>;
+
+    fn set_auto_compound(e: Env, miner: Address, enabled: bool);
+
+    fn auto_compound(e: Env, miner: Address) -> bool;
+
+    fn set_emission_breaker(e: Env, window: u64, ceiling: u128, defer: bool);
+
+    fn emission_breaker(e: Env) -> Option<EmissionBreaker>;
+
+    fn claim_deferred(e: Env, miner: Address);
+
+    fn set_staking_cut(e: Env, cut_bps: u32);
+
+    fn staking_cut(e: Env) -> u32;
+
+    fn total_staked(e: Env) -> u128;
+
+    fn claim_staking_rewards(e: Env, staker: Address) -> u128;
+
+    fn staking_rewards(e: Env, staker: Address) -> u128;
+
+    fn deferred_rewards(e: Env, miner: Address) -> u128;
 }
 
 #[contract]
@@ -38,26 +278,221 @@ pub struct ReactorContract;
 
 #[contractimpl]
 impl ReactorContractTrait for ReactorContract {
-    fn upgrade(e: Env, hash: BytesN<32>) {
-        get_state(&e).unwrap().finder.require_auth();
-        e.deployer().update_current_contract_wasm(hash);
+    // Upgrades are announced UPGRADE_DELAY before they can be executed, while one is pending every
+    // stake can be taken out at once so miners that don't agree with the new code can leave
+    fn schedule_upgrade(e: Env, caller: Address, hash: BytesN<32>) {
+        let state: ReactorState = get_state(&e).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::NonDiscovered);
+        });
+        require_role(&e, &state, &Role::Upgrader, &caller);
+
+        if get_pending_upgrade(&e).is_some() {
+            panic_with_error!(&e, &ContractErrors::UpgradeAlreadyScheduled);
+        }
+
+        let upgrade: PendingUpgrade = PendingUpgrade {
+            hash,
+            executable_at: e.ledger().timestamp() + UPGRADE_DELAY,
+        };
+        set_pending_upgrade(&e, &upgrade);
+
+        e.events().publish(
+            (symbol_short!("upgrade"), symbol_short!("scheduled")),
+            (upgrade.hash, upgrade.executable_at),
+        );
+        pump_core(&e);
+    }
+
+    fn execute_upgrade(e: Env, caller: Address) {
+        let state: ReactorState = get_state(&e).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::NonDiscovered);
+        });
+        require_role(&e, &state, &Role::Upgrader, &caller);
+
+        let upgrade: PendingUpgrade = get_pending_upgrade(&e).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::NoUpgradeScheduled);
+        });
+
+        if e.ledger().timestamp() < upgrade.executable_at {
+            panic_with_error!(&e, &ContractErrors::UpgradeIsTimeLocked);
+        }
+
+        delete_pending_upgrade(&e);
+        e.events().publish(
+            (symbol_short!("upgrade"), symbol_short!("executed")),
+            upgrade.hash.clone(),
+        );
+        e.deployer().update_current_contract_wasm(upgrade.hash);
+    }
+
+    fn cancel_upgrade(e: Env, caller: Address) {
+        let state: ReactorState = get_state(&e).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::NonDiscovered);
+        });
+        require_role(&e, &state, &Role::Upgrader, &caller);
+
+        let upgrade: PendingUpgrade = get_pending_upgrade(&e).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::NoUpgradeScheduled);
+        });
+
+        delete_pending_upgrade(&e);
+        e.events().publish(
+            (symbol_short!("upgrade"), symbol_short!("canceled")),
+            upgrade.hash,
+        );
+        pump_core(&e);
     }
 
-    fn set_difficulty(e: &Env, difficulty: u32) {
-        let mut state = get_state(&e).unwrap();
-        state.finder.require_auth();
+    fn pending_upgrade(e: Env) -> Option<PendingUpgrade> {
+        get_pending_upgrade(&e)
+    }
+
+    fn set_difficulty(e: &Env, caller: Address, difficulty: u32) {
+        let mut state: ReactorState = get_state(e).unwrap_or_else(|| {
+            panic_with_error!(e, &ContractErrors::NonDiscovered);
+        });
+        require_role(e, &state, &Role::DifficultyOperator, &caller);
         state.difficulty = difficulty;
-        set_state(&e, &state);
+        set_state(e, &state);
+        pump_core(e);
+    }
+
+    // Called once by the upgrader after an upgrade that changes the schema, entries that can't be
+    // migrated at once (like the blocks) are migrated when they are read
+    fn migrate(e: Env, caller: Address) {
+        let state: ReactorState = get_state(&e).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::NonDiscovered);
+        });
+        require_role(&e, &state, &Role::Upgrader, &caller);
+
+        if get_schema_version(&e) >= SCHEMA_VERSION {
+            panic_with_error!(&e, &ContractErrors::AlreadyMigrated);
+        }
+
+        // Reading the state already rewrote it, the roles of the finder are written down too
+        migrate_roles(&e, &state);
+
+        // There is no way to know how much of the FCM in the reactor belongs to its users, so all of
+        // it (but the prize pool, which is tracked on its own) is considered theirs
+        if get_held_fcm(&e).is_none() {
+            let balance: i128 =
+                token::Client::new(&e, &state.fcm).balance(&e.current_contract_address());
+            set_held_fcm(
+                &e,
+                &(balance as u128).saturating_sub(get_prize_pool(&e, &state.fcm)),
+            );
+        }
+
+        set_schema_version(&e, &SCHEMA_VERSION);
+        e.events().publish(
+            (symbol_short!("schema"), symbol_short!("migrated")),
+            SCHEMA_VERSION,
+        );
         pump_core(&e);
     }
 
-    fn find(e: Env, fcm: Address, miner: Address, message: String) {
-        miner.require_auth();
+    fn schema_version(e: Env) -> u32 {
+        get_schema_version(&e)
+    }
+
+    fn admin(e: Env) -> Address {
+        let state: ReactorState = get_state(&e).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::NonDiscovered);
+        });
+        admin(&e, &state)
+    }
+
+    // The new admin needs to accept the transfer, so the reactor can't end up in an unusable address
+    fn propose_admin(e: Env, new_admin: Address) {
+        let state: ReactorState = get_state(&e).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::NonDiscovered);
+        });
+        admin(&e, &state).require_auth();
+        migrate_roles(&e, &state);
+
+        set_pending_admin(&e, &new_admin);
+        e.events().publish(
+            (symbol_short!("admin"), symbol_short!("proposed")),
+            new_admin,
+        );
+        pump_core(&e);
+    }
+
+    fn accept_admin(e: Env) {
+        let new_admin: Address = get_pending_admin(&e).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::NoPendingAdmin);
+        });
+        new_admin.require_auth();
+
+        set_admin(&e, &new_admin);
+        delete_pending_admin(&e);
+        e.events().publish(
+            (symbol_short!("admin"), symbol_short!("accepted")),
+            new_admin,
+        );
+        pump_core(&e);
+    }
+
+    fn pending_admin(e: Env) -> Option<Address> {
+        get_pending_admin(&e)
+    }
+
+    fn grant_role(e: Env, role: Role, account: Address) {
+        let state: ReactorState = get_state(&e).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::NonDiscovered);
+        });
+        admin(&e, &state).require_auth();
+        migrate_roles(&e, &state);
+
+        grant_role(&e, &role, &account);
+        pump_core(&e);
+    }
+
+    fn revoke_role(e: Env, role: Role, account: Address) {
+        let state: ReactorState = get_state(&e).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::NonDiscovered);
+        });
+        admin(&e, &state).require_auth();
+        migrate_roles(&e, &state);
+
+        set_role_member(&e, &role, &account, false);
+        e.events().publish(
+            (symbol_short!("role"), symbol_short!("revoked"), role),
+            account,
+        );
+        pump_core(&e);
+    }
+
+    fn has_role(e: Env, role: Role, account: Address) -> bool {
+        let state: ReactorState = get_state(&e).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::NonDiscovered);
+        });
+        has_role(&e, &state, &role, &account)
+    }
+
+    // Only the account that deployed the reactor (with the given salt) can initialize it, so no one
+    // can front-run the initialization between the deployment and this call
+    fn initialize(e: Env, admin: Address, salt: BytesN<32>, config: ReactorConfig) {
+        admin.require_auth();
 
         if get_state(&e).is_some() {
             panic_with_error!(&e, &ContractErrors::AlreadyDiscovered);
         }
 
+        if e.deployer()
+            .with_address(admin.clone(), salt)
+            .deployed_address()
+            != e.current_contract_address()
+        {
+            panic_with_error!(&e, &ContractErrors::NotTheDeployer);
+        }
+
+        // The reactor mints the FCM, so it must be the admin of the asset
+        let fcm_admin = token::StellarAssetClient::new(&e, &config.fcm).try_admin();
+        if fcm_admin != Ok(Ok(e.current_contract_address())) {
+            panic_with_error!(&e, &ContractErrors::ReactorIsNotFcmAdmin);
+        }
+
         let prev_hash: BytesN<32> = BytesN::from_array(
             &e,
             &[
@@ -66,14 +501,16 @@ impl ReactorContractTrait for ReactorContract {
             ],
         );
         let nonce: u64 = 0;
-        let hash: BytesN<32> = create_block_hash(&e, &0, &message, &prev_hash, &nonce, &miner);
+        let hash: BytesN<32> =
+            create_block_hash(&e, &0, &config.genesis_message, &prev_hash, &nonce, &admin);
 
+        // The admin is the finder of the mine, the genesis block is theirs
         let new_attempt: Block = Block {
             index: 0,
-            message,
+            message: config.genesis_message,
             prev_hash,
             nonce,
-            miner: miner.clone(),
+            miner: admin.clone(),
             hash,
             timestamp: e.ledger().timestamp(),
         };
@@ -81,16 +518,19 @@ impl ReactorContractTrait for ReactorContract {
         set_block(&e, &new_attempt);
         pump_block(&e, &new_attempt.index);
 
-        set_state(
-            &e,
-            &ReactorState {
-                fcm,
-                current: 0,
-                difficulty: 0,
-                is_nuked: false,
-                finder: miner,
-            },
-        );
+        let state: ReactorState = ReactorState {
+            fcm: config.fcm,
+            current: 0,
+            difficulty: config.difficulty,
+            is_nuked: false,
+            finder: admin,
+        };
+        set_state(&e, &state);
+        set_schema_version(&e, &SCHEMA_VERSION);
+        set_held_fcm(&e, &0);
+
+        // The finder starts as the admin and with every role
+        migrate_roles(&e, &state);
 
         pump_core(&e);
     }
@@ -110,6 +550,9 @@ impl ReactorContractTrait for ReactorContract {
             panic_with_error!(&e, &ContractErrors::TheMineWasNuked);
         }
 
+        require_not_paused(&e);
+        require_miner_admitted(&e, &miner);
+
         if state.current >= MAX_SUPPLY {
             panic_with_error!(&e, &ContractErrors::NoMoreSupplyAvailable);
         }
@@ -124,13 +567,9 @@ impl ReactorContractTrait for ReactorContract {
             panic_with_error!(&e, &ContractErrors::ProvidedHashIsInvalid);
         }
 
-        let stake: Stake = get_stake(&e, &miner).unwrap_or(Stake {
-            owner: miner.clone(),
-            amount: 0,
-            cools_at: 0,
-        });
+        let staked: u128 = stake_power(&e, &miner, &read_stake_lots(&e, &miner));
 
-        if (stake.amount / 1_0000000) < (state.current / STAKING_DIVISOR) as u128 {
+        if !is_stake_enough(&e, &state, &staked) {
             panic_with_error!(&e, &ContractErrors::NotEnoughStaked);
         }
 
@@ -139,23 +578,35 @@ impl ReactorContractTrait for ReactorContract {
         }
 
         // We try to save the miner attempt, if there is no more space we just ignore it
+        // Each miner takes one ticket (position) in the attempt plus the extra ones from its boost
         let mut attempt: Attempt = get_attempt(&e, &(state.current + 1)).unwrap_or(Attempt {
             block: state.current + 1,
             total_miners: 0,
         });
-        if attempt.total_miners < 255 {
-            if get_miner_attempt(&e, &attempt.block, &miner).is_none() {
-                attempt.total_miners += 1;
-                set_attempt(&e, &attempt.block, &attempt);
-                let miner_attempt = MinerAttempt {
-                    block: attempt.block,
-                    miner: miner.clone(),
-                    position: attempt.total_miners,
-                };
-                set_miner_attempt_index(&e, &miner_attempt);
-                set_miner_attempt(&e, &miner_attempt);
+        if attempt.total_miners < MAX_ATTEMPT_TICKETS
+            && get_miner_attempt(&e, &attempt.block, &miner).is_none()
+        {
+            let tickets: u32 = miner_tickets(&e, &state, &attempt.block, &miner)
+                .min(MAX_ATTEMPT_TICKETS - attempt.total_miners);
+            let miner_attempt = MinerAttempt {
+                block: attempt.block,
+                miner: miner.clone(),
+                position: attempt.total_miners + 1,
             };
-        }
+            set_miner_attempt(&e, &miner_attempt);
+            for position in miner_attempt.position..(miner_attempt.position + tickets) {
+                set_miner_attempt_index(
+                    &e,
+                    &MinerAttempt {
+                        block: attempt.block,
+                        miner: miner.clone(),
+                        position,
+                    },
+                );
+            }
+            attempt.total_miners += tickets;
+            set_attempt(&e, &attempt.block, &attempt);
+        };
 
         // Mutation is not enabled at the moment
         // mutate_stake_position(&e, &state, &miner);
@@ -182,11 +633,9 @@ impl ReactorContractTrait for ReactorContract {
             pump_block(&e, &new_attempt.index);
 
             // The protocol tries to send the last found amount based on time to find the block
-            match get_block(&e, &(prev_attempt.index.saturating_sub(1))) {
-                None => {
-                    let _ = token::StellarAssetClient::new(&e, &state.fcm)
-                        .try_mint(&prev_attempt.miner, &1_0000000);
-                }
+            let amount_to_send: i128 = match get_block(&e, &(prev_attempt.index.saturating_sub(1)))
+            {
+                None => 1_0000000,
                 Some(block_before) => {
                     let seconds_to_find: u64 = prev_attempt
                         .timestamp
@@ -196,11 +645,19 @@ impl ReactorContractTrait for ReactorContract {
                     if amount_to_send == 0 {
                         amount_to_send += 1_0000000i128;
                     }
-                    let _ = token::StellarAssetClient::new(&e, &state.fcm)
-                        .try_mint(&prev_attempt.miner, &amount_to_send);
+                    amount_to_send
                 }
+            };
+            let allowed_amount: i128 =
+                apply_emission_breaker(&e, &prev_attempt.miner, &amount_to_send);
+            let miner_amount: i128 =
+                allowed_amount - feed_staking_rewards(&e, &state, &allowed_amount);
+            if miner_amount > 0 {
+                share_block_reward(&e, &state, &prev_attempt.miner, &miner_amount);
             }
 
+            drip_prize_pool(&e, &prev_attempt.miner);
+
             // We update the index to the new attempt
             state.current = new_index;
         }
@@ -216,97 +673,1882 @@ impl ReactorContractTrait for ReactorContract {
             panic_with_error!(&e, &ContractErrors::NonDiscovered);
         });
 
+        if state.is_nuked {
+            panic_with_error!(&e, &ContractErrors::TheMineWasNuked);
+        }
+
+        require_not_paused(&e);
+
         token::Client::new(&e, &state.fcm).transfer(
             &miner,
             &e.current_contract_address(),
             &(amount as i128),
         );
-
-        let mut stake: Stake = get_stake(&e, &miner).unwrap_or(Stake {
-            owner: miner.clone(),
-            amount: 0,
-            cools_at: 0,
-        });
-
-        stake.cools_at = e.ledger().timestamp() + (3600 * 24 * 60);
-        stake.amount += amount;
-        set_stake(&e, &stake);
+        hold_fcm(&e, &amount);
+
+        let mut lots: Vec<StakeLot> = read_stake_lots(&e, &miner);
+        add_stake_lot(
+            &mut lots,
+            StakeLot {
+                amount,
+                cools_at: e.ledger().timestamp() + STAKE_COOLDOWN,
+            },
+        );
+        set_stake_lots(&e, &miner, &lots);
+        sync_staking_rewards(&e, &miner, &lots);
+        checkpoint_voting_power(&e, &miner);
+
+        // The receipts are kept in the reactor for the staker, it can take them out to move them
+        if let Some(receipt) = get_receipt_token(&e) {
+            token::StellarAssetClient::new(&e, &receipt)
+                .mint(&e.current_contract_address(), &(amount as i128));
+            set_receipted_stake(&e, &miner, &(get_receipted_stake(&e, &miner) + amount));
+            pump_receipted_stake(&e, &miner);
+            deposit_receipts(&e, &miner, &amount);
+        }
 
         pump_stake(&e, &miner);
         pump_core(&e);
     }
 
-    fn un_stake(e: Env, miner: Address) {
+    fn un_stake(e: Env, miner: Address, amount: u128) {
         miner.require_auth();
 
         let state: ReactorState = get_state(&e).unwrap_or_else(|| {
             panic_with_error!(&e, &ContractErrors::NonDiscovered);
         });
 
-        let stake: Stake = get_stake(&e, &miner).unwrap_or(Stake {
-            owner: miner.clone(),
-            amount: 0,
-            cools_at: 0,
-        });
+        if amount == 0 {
+            panic_with_error!(&e, &ContractErrors::InvalidAmount);
+        }
+
+        let lots: Vec<StakeLot> = read_stake_lots(&e, &miner);
 
-        if stake.amount == 0 {
+        if lots.is_empty() {
             panic_with_error!(&e, &ContractErrors::NothingToWithdraw);
         }
 
-        if stake.cools_at >= e.ledger().timestamp() {
+        // Only the lots that already cooled down can be released, the oldest ones go first
+        let now: u64 = exit_time(&e, &state);
+        let mut remaining_lots: Vec<StakeLot> = Vec::new(&e);
+        let mut pending: u128 = amount;
+        for mut lot in lots.iter() {
+            if pending > 0 && lot.cools_at < now {
+                let released: u128 = pending.min(lot.amount);
+                lot.amount -= released;
+                pending -= released;
+            }
+
+            if lot.amount > 0 {
+                remaining_lots.push_back(lot);
+            }
+        }
+
+        if pending > 0 {
             panic_with_error!(&e, &ContractErrors::StakeIsStillHot);
         }
 
+        redeem_receipts(&e, &miner, &total_staked(&lots), &amount);
+
+        // The stake delegated to miners can't be taken until the delegations are revoked
+        if total_staked(&remaining_lots).saturating_sub(get_receipted_stake(&e, &miner))
+            < get_delegated_out(&e, &miner)
+        {
+            panic_with_error!(&e, &ContractErrors::StakeIsDelegated);
+        }
+
+        // A miner already in the current attempt must keep enough stake to be there
+        if !state.is_nuked
+            && get_miner_attempt(&e, &(state.current + 1), &miner).is_some()
+            && !is_stake_enough(&e, &state, &stake_power(&e, &miner, &remaining_lots))
+        {
+            panic_with_error!(&e, &ContractErrors::StakeBelowRequirement);
+        }
+
         token::Client::new(&e, &state.fcm).transfer(
             &e.current_contract_address(),
             &miner,
-            &(stake.amount as i128),
+            &(amount as i128),
         );
+        release_fcm(&e, &amount);
 
-        delete_stake(&e, &miner);
+        set_stake_lots(&e, &miner, &remaining_lots);
+        sync_staking_rewards(&e, &miner, &remaining_lots);
+        checkpoint_voting_power(&e, &miner);
+        pump_stake(&e, &miner);
         pump_core(&e);
     }
 
-    fn fkin_nuke_it(e: Env, caller: Address) {
-        caller.require_auth();
+    fn stake_lots(e: Env, miner: Address) -> Vec<StakeLot> {
+        read_stake_lots(&e, &miner)
+    }
 
-        let mut state: ReactorState = get_state(&e).unwrap_or_else(|| {
-            panic_with_error!(&e, &ContractErrors::NonDiscovered);
-        });
+    // Anyone can keep a stake (and everything attached to it) from being archived, stakes from
+    // before the governance existed also get their voting power registered
+    fn bump_stake(e: Env, miner: Address) {
+        checkpoint_voting_power(&e, &miner);
+        pump_stake(&e, &miner);
+        pump_delegated(&e, &miner);
 
-        if state.is_nuked {
-            panic_with_error!(&e, &ContractErrors::TheMineWasNuked);
+        // Lots of delisted assets are extended too, they can still be withdrawn
+        for asset in get_stake_assets(&e).iter() {
+            pump_asset_stake_lots(&e, &miner, &asset.token);
         }
+        for token in get_owner_assets(&e, &miner).iter() {
+            pump_asset_stake_lots(&e, &miner, &token);
+        }
+        pump_owner_assets(&e, &miner);
 
-        if caller != state.finder {
-            panic_with_error!(&e, &ContractErrors::NotTheFinder);
+        if get_ve_lock(&e, &miner).is_some() {
+            pump_ve_lock(&e, &miner);
         }
 
-        state.is_nuked = true;
-        set_state(&e, &state);
+        if get_hot_rewards(&e, &miner).is_some() {
+            pump_hot_rewards(&e, &miner);
+        }
+
+        pump_core(&e);
     }
-}
 
-pub fn create_block_hash(
-    e: &Env,
-    index: &u64,
-    message: &String,
-    prev_hash: &BytesN<32>,
-    nonce: &u64,
-    miner: &Address,
-) -> BytesN<32> {
-    let mut builder: Bytes = Bytes::new(&e);
-    builder.append(&index.to_xdr(&e));
-    builder.append(&message.clone().to_xdr(&e));
-    builder.append(&prev_hash.clone().to_xdr(&e));
-    builder.append(&nonce.to_xdr(&e));
-    builder.append(&miner.to_xdr(&e));
+    fn emergency_unstake(e: Env, miner: Address) -> UnstakeQuote {
+        miner.require_auth();
 
-    e.crypto().keccak256(&builder).to_bytes()
-}
+        let state: ReactorState = get_state(&e).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::NonDiscovered);
+        });
 
-pub fn is_difficulty_correct(hash: &BytesN<32>, difficulty: &u32) -> bool {
-    let hex = encode(hash.to_array());
+        let lots: Vec<StakeLot> = read_stake_lots(&e, &miner);
+
+        if lots.is_empty() {
+            panic_with_error!(&e, &ContractErrors::NothingToWithdraw);
+        }
+
+        if get_delegated_out(&e, &miner) > 0 {
+            panic_with_error!(&e, &ContractErrors::StakeIsDelegated);
+        }
+
+        let staked: u128 = total_staked(&lots);
+        redeem_receipts(&e, &miner, &staked, &staked);
+
+        if !state.is_nuked
+            && get_miner_attempt(&e, &(state.current + 1), &miner).is_some()
+            && !is_stake_enough(&e, &state, &stake_power(&e, &miner, &Vec::new(&e)))
+        {
+            panic_with_error!(&e, &ContractErrors::StakeBelowRequirement);
+        }
+
+        let quote: UnstakeQuote = quote_emergency_unstake(&lots, &exit_time(&e, &state));
+
+        if quote.penalty > 0 {
+            token::Client::new(&e, &state.fcm)
+                .burn(&e.current_contract_address(), &(quote.penalty as i128));
+        }
+
+        if quote.amount > 0 {
+            token::Client::new(&e, &state.fcm).transfer(
+                &e.current_contract_address(),
+                &miner,
+                &(quote.amount as i128),
+            );
+        }
+        release_fcm(&e, &staked);
+
+        set_stake_lots(&e, &miner, &Vec::new(&e));
+        sync_staking_rewards(&e, &miner, &Vec::new(&e));
+        checkpoint_voting_power(&e, &miner);
+        pump_core(&e);
+
+        quote
+    }
+
+    fn emergency_unstake_quote(e: Env, miner: Address) -> UnstakeQuote {
+        let now: u64 = match get_state(&e) {
+            Some(state) => exit_time(&e, &state),
+            None => e.ledger().timestamp(),
+        };
+        quote_emergency_unstake(&read_stake_lots(&e, &miner), &now)
+    }
+
+    fn set_stake_assets(e: Env, assets: Vec<StakeAsset>) {
+        let state: ReactorState = get_state(&e).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::NonDiscovered);
+        });
+        admin(&e, &state).require_auth();
+
+        if assets.len() > MAX_STAKE_ASSETS {
+            panic_with_error!(&e, &ContractErrors::InvalidStakeAssets);
+        }
+
+        // FCM is already the main stake, accepting it here would let it count twice
+        for (i, asset) in assets.iter().enumerate() {
+            let is_repeated: bool = assets
+                .iter()
+                .take(i)
+                .any(|previous| previous.token == asset.token);
+
+            if asset.token == state.fcm || asset.weight == 0 || is_repeated {
+                panic_with_error!(&e, &ContractErrors::InvalidStakeAssets);
+            }
+        }
+
+        set_stake_assets(&e, &assets);
+        pump_core(&e);
+    }
+
+    fn stake_assets(e: Env) -> Vec<StakeAsset> {
+        get_stake_assets(&e)
+    }
+
+    fn stake_asset(e: Env, miner: Address, token: Address, amount: u128) {
+        miner.require_auth();
+
+        let state: ReactorState = get_state(&e).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::NonDiscovered);
+        });
+
+        if state.is_nuked {
+            panic_with_error!(&e, &ContractErrors::TheMineWasNuked);
+        }
+
+        require_not_paused(&e);
+
+        if amount == 0 {
+            panic_with_error!(&e, &ContractErrors::InvalidAmount);
+        }
+
+        if !get_stake_assets(&e)
+            .iter()
+            .any(|asset| asset.token == token)
+        {
+            panic_with_error!(&e, &ContractErrors::StakeAssetNotAccepted);
+        }
+
+        token::Client::new(&e, &token).transfer(
+            &miner,
+            &e.current_contract_address(),
+            &(amount as i128),
+        );
+
+        let mut lots: Vec<StakeLot> = get_asset_stake_lots(&e, &miner, &token);
+        add_stake_lot(
+            &mut lots,
+            StakeLot {
+                amount,
+                cools_at: e.ledger().timestamp() + STAKE_COOLDOWN,
+            },
+        );
+        set_asset_stake_lots(&e, &miner, &token, &lots);
+        set_asset_staked(&e, &token, &(get_asset_staked(&e, &token) + amount));
+
+        pump_asset_stake_lots(&e, &miner, &token);
+        pump_owner_assets(&e, &miner);
+        pump_asset_staked(&e, &token);
+        pump_core(&e);
+    }
+
+    // Delisted assets don't count as stake anymore but can still be taken back
+    fn un_stake_asset(e: Env, miner: Address, token: Address, amount: u128) {
+        miner.require_auth();
+
+        let state: ReactorState = get_state(&e).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::NonDiscovered);
+        });
+
+        if amount == 0 {
+            panic_with_error!(&e, &ContractErrors::InvalidAmount);
+        }
+
+        let lots: Vec<StakeLot> = get_asset_stake_lots(&e, &miner, &token);
+
+        if lots.is_empty() {
+            panic_with_error!(&e, &ContractErrors::NothingToWithdraw);
+        }
+
+        let now: u64 = exit_time(&e, &state);
+        let mut remaining_lots: Vec<StakeLot> = Vec::new(&e);
+        let mut pending: u128 = amount;
+        for mut lot in lots.iter() {
+            if pending > 0 && lot.cools_at < now {
+                let released: u128 = pending.min(lot.amount);
+                lot.amount -= released;
+                pending -= released;
+            }
+
+            if lot.amount > 0 {
+                remaining_lots.push_back(lot);
+            }
+        }
+
+        if pending > 0 {
+            panic_with_error!(&e, &ContractErrors::StakeIsStillHot);
+        }
+
+        set_asset_stake_lots(&e, &miner, &token, &remaining_lots);
+
+        if !state.is_nuked
+            && get_miner_attempt(&e, &(state.current + 1), &miner).is_some()
+            && !is_stake_enough(
+                &e,
+                &state,
+                &stake_power(&e, &miner, &read_stake_lots(&e, &miner)),
+            )
+        {
+            panic_with_error!(&e, &ContractErrors::StakeBelowRequirement);
+        }
+
+        token::Client::new(&e, &token).transfer(
+            &e.current_contract_address(),
+            &miner,
+            &(amount as i128),
+        );
+
+        set_asset_staked(&e, &token, &(get_asset_staked(&e, &token) - amount));
+        pump_asset_stake_lots(&e, &miner, &token);
+        pump_asset_staked(&e, &token);
+        pump_core(&e);
+    }
+
+    fn asset_stake_lots(e: Env, miner: Address, token: Address) -> Vec<StakeLot> {
+        get_asset_stake_lots(&e, &miner, &token)
+    }
+
+    fn ve_lock(e: Env, owner: Address, amount: u128, duration: u64) {
+        owner.require_auth();
+
+        let state: ReactorState = get_state(&e).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::NonDiscovered);
+        });
+
+        if state.is_nuked {
+            panic_with_error!(&e, &ContractErrors::TheMineWasNuked);
+        }
+
+        require_not_paused(&e);
+
+        if amount == 0 {
+            panic_with_error!(&e, &ContractErrors::InvalidAmount);
+        }
+
+        if !(VE_MIN_LOCK..=VE_MAX_LOCK).contains(&duration) {
+            panic_with_error!(&e, &ContractErrors::InvalidLockDuration);
+        }
+
+        if get_ve_lock(&e, &owner).is_some() {
+            panic_with_error!(&e, &ContractErrors::VeLockAlreadyExists);
+        }
+
+        token::Client::new(&e, &state.fcm).transfer(
+            &owner,
+            &e.current_contract_address(),
+            &(amount as i128),
+        );
+        hold_fcm(&e, &amount);
+
+        set_ve_lock(
+            &e,
+            &VeLock {
+                owner: owner.clone(),
+                amount,
+                unlocks_at: e.ledger().timestamp() + duration,
+            },
+        );
+        checkpoint_voting_power(&e, &owner);
+
+        pump_ve_lock(&e, &owner);
+        pump_core(&e);
+    }
+
+    fn ve_increase(e: Env, owner: Address, amount: u128) {
+        owner.require_auth();
+
+        let state: ReactorState = get_state(&e).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::NonDiscovered);
+        });
+
+        if state.is_nuked {
+            panic_with_error!(&e, &ContractErrors::TheMineWasNuked);
+        }
+
+        require_not_paused(&e);
+
+        if amount == 0 {
+            panic_with_error!(&e, &ContractErrors::InvalidAmount);
+        }
+
+        let mut lock: VeLock = get_ve_lock(&e, &owner).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::NothingToWithdraw);
+        });
+
+        if lock.unlocks_at <= e.ledger().timestamp() {
+            panic_with_error!(&e, &ContractErrors::VeLockExpired);
+        }
+
+        token::Client::new(&e, &state.fcm).transfer(
+            &owner,
+            &e.current_contract_address(),
+            &(amount as i128),
+        );
+        hold_fcm(&e, &amount);
+
+        lock.amount += amount;
+        set_ve_lock(&e, &lock);
+        checkpoint_voting_power(&e, &owner);
+
+        pump_ve_lock(&e, &owner);
+        pump_core(&e);
+    }
+
+    fn ve_extend(e: Env, owner: Address, duration: u64) {
+        owner.require_auth();
+
+        if get_state(&e).is_none() {
+            panic_with_error!(&e, &ContractErrors::NonDiscovered);
+        }
+
+        let mut lock: VeLock = get_ve_lock(&e, &owner).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::NothingToWithdraw);
+        });
+
+        // The lock can only be extended, even if it already expired
+        let unlocks_at: u64 = e.ledger().timestamp() + duration;
+        if duration > VE_MAX_LOCK || unlocks_at <= lock.unlocks_at {
+            panic_with_error!(&e, &ContractErrors::InvalidLockDuration);
+        }
+
+        lock.unlocks_at = unlocks_at;
+        set_ve_lock(&e, &lock);
+        checkpoint_voting_power(&e, &owner);
+
+        pump_ve_lock(&e, &owner);
+        pump_core(&e);
+    }
+
+    fn ve_withdraw(e: Env, owner: Address) {
+        owner.require_auth();
+
+        let state: ReactorState = get_state(&e).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::NonDiscovered);
+        });
+
+        let lock: VeLock = get_ve_lock(&e, &owner).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::NothingToWithdraw);
+        });
+
+        if lock.unlocks_at > exit_time(&e, &state) {
+            panic_with_error!(&e, &ContractErrors::StakeIsStillHot);
+        }
+
+        token::Client::new(&e, &state.fcm).transfer(
+            &e.current_contract_address(),
+            &owner,
+            &(lock.amount as i128),
+        );
+        release_fcm(&e, &lock.amount);
+
+        delete_ve_lock(&e, &owner);
+        checkpoint_voting_power(&e, &owner);
+        pump_core(&e);
+    }
+
+    fn ve_lock_of(e: Env, owner: Address) -> Option<VeLock> {
+        get_ve_lock(&e, &owner)
+    }
+
+    fn effective_stake(e: Env, miner: Address) -> u128 {
+        stake_power(&e, &miner, &read_stake_lots(&e, &miner))
+    }
+
+    fn set_requirement_curve(e: Env, curve: RequirementCurve) {
+        let state: ReactorState = get_state(&e).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::NonDiscovered);
+        });
+        admin(&e, &state).require_auth();
+        check_requirement_curve(&e, &curve);
+        set_requirement_curve(&e, &curve);
+        pump_core(&e);
+    }
+
+    fn requirement_curve(e: Env) -> RequirementCurve {
+        requirement_curve(&e)
+    }
+
+    // `at` is a block index or an amount of whole FCM minted depending on the basis of the curve
+    fn stake_requirement(e: Env, at: u64) -> u128 {
+        calculate_stake_requirement(&requirement_curve(&e), &at)
+    }
+
+    fn total_minted(e: Env) -> u128 {
+        get_total_minted(&e)
+    }
+
+    fn set_receipt_token(e: Env, token: Address) {
+        let state: ReactorState = get_state(&e).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::NonDiscovered);
+        });
+        admin(&e, &state).require_auth();
+
+        // Changing the receipt would leave the receipts already minted without backing
+        if get_receipt_token(&e).is_some() {
+            panic_with_error!(&e, &ContractErrors::ReceiptTokenAlreadySet);
+        }
+
+        let admin = token::StellarAssetClient::new(&e, &token).try_admin();
+        if admin != Ok(Ok(e.current_contract_address())) {
+            panic_with_error!(&e, &ContractErrors::InvalidReceiptToken);
+        }
+
+        set_receipt_token(&e, &token);
+        pump_core(&e);
+    }
+
+    fn receipt_token(e: Env) -> Option<Address> {
+        get_receipt_token(&e)
+    }
+
+    fn receipted_stake(e: Env, miner: Address) -> u128 {
+        get_receipted_stake(&e, &miner)
+    }
+
+    fn deposit_receipt(e: Env, holder: Address, amount: u128) {
+        holder.require_auth();
+
+        let state: ReactorState = get_state(&e).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::NonDiscovered);
+        });
+
+        if state.is_nuked {
+            panic_with_error!(&e, &ContractErrors::TheMineWasNuked);
+        }
+
+        require_not_paused(&e);
+
+        let receipt: Address = get_receipt_token(&e).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::InvalidReceiptToken);
+        });
+
+        if amount == 0 {
+            panic_with_error!(&e, &ContractErrors::InvalidAmount);
+        }
+
+        token::Client::new(&e, &receipt).transfer(
+            &holder,
+            &e.current_contract_address(),
+            &(amount as i128),
+        );
+        deposit_receipts(&e, &holder, &amount);
+
+        pump_stake(&e, &holder);
+        pump_core(&e);
+    }
+
+    fn withdraw_receipt(e: Env, holder: Address, amount: u128) {
+        holder.require_auth();
+
+        let state: ReactorState = get_state(&e).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::NonDiscovered);
+        });
+
+        let receipt: Address = get_receipt_token(&e).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::InvalidReceiptToken);
+        });
+
+        if amount == 0 {
+            panic_with_error!(&e, &ContractErrors::InvalidAmount);
+        }
+
+        if amount > get_receipt_deposit(&e, &holder) {
+            panic_with_error!(&e, &ContractErrors::NotEnoughReceipts);
+        }
+
+        withdraw_receipts(&e, &holder, &amount);
+
+        // Receipts can't leave while the holder needs them in the current attempt, otherwise the
+        // same stake could get other addresses into the same block
+        if !state.is_nuked
+            && get_miner_attempt(&e, &(state.current + 1), &holder).is_some()
+            && !is_stake_enough(
+                &e,
+                &state,
+                &stake_power(&e, &holder, &read_stake_lots(&e, &holder)),
+            )
+        {
+            panic_with_error!(&e, &ContractErrors::StakeBelowRequirement);
+        }
+
+        token::Client::new(&e, &receipt).transfer(
+            &e.current_contract_address(),
+            &holder,
+            &(amount as i128),
+        );
+
+        pump_stake(&e, &holder);
+        pump_core(&e);
+    }
+
+    fn receipt_deposit(e: Env, holder: Address) -> u128 {
+        get_receipt_deposit(&e, &holder)
+    }
+
+    fn delegate_stake(e: Env, owner: Address, miner: Address, amount: u128) {
+        owner.require_auth();
+
+        let state: ReactorState =
+            get_state(&e).unwrap_or_else(|| panic_with_error!(&e, &ContractErrors::NonDiscovered));
+
+        if amount == 0 {
+            panic_with_error!(&e, &ContractErrors::InvalidAmount);
+        }
+
+        // Dust delegations would let anyone fill the delegator slots of a miner
+        if amount < MIN_DELEGATION {
+            panic_with_error!(&e, &DelegationErrors::DelegationTooSmall);
+        }
+
+        if owner == miner {
+            panic_with_error!(&e, &ContractErrors::InvalidDelegation);
+        }
+
+        if delegable_stake(&e, &owner) < amount {
+            panic_with_error!(&e, &ContractErrors::NotEnoughToDelegate);
+        }
+
+        let mut delegators: Vec<Address> = get_delegators(&e, &miner);
+        if !delegators.contains(&owner) {
+            if delegators.len() >= MAX_DELEGATORS {
+                panic_with_error!(&e, &ContractErrors::TooManyDelegators);
+            }
+            delegators.push_back(owner.clone());
+            set_delegators(&e, &miner, &delegators);
+        }
+
+        set_delegation(
+            &e,
+            &owner,
+            &miner,
+            &(get_delegation(&e, &owner, &miner) + amount),
+        );
+        set_delegated_out(&e, &owner, &(get_delegated_out(&e, &owner) + amount));
+        set_delegated_in(&e, &miner, &(get_delegated_in(&e, &miner) + amount));
+
+        // The owner can't hand its stake to someone else while it keeps the owner in the current attempt
+        if !state.is_nuked
+            && get_miner_attempt(&e, &(state.current + 1), &owner).is_some()
+            && !is_stake_enough(
+                &e,
+                &state,
+                &stake_power(&e, &owner, &read_stake_lots(&e, &owner)),
+            )
+        {
+            panic_with_error!(&e, &ContractErrors::StakeBelowRequirement);
+        }
+
+        pump_delegation(&e, &owner, &miner);
+        pump_delegated(&e, &owner);
+        pump_delegated(&e, &miner);
+        pump_stake(&e, &owner);
+        pump_core(&e);
+    }
+
+    fn revoke_delegation(e: Env, owner: Address, miner: Address) {
+        owner.require_auth();
+
+        let state: ReactorState =
+            get_state(&e).unwrap_or_else(|| panic_with_error!(&e, &ContractErrors::NonDiscovered));
+
+        let amount: u128 = get_delegation(&e, &owner, &miner);
+        if amount == 0 {
+            panic_with_error!(&e, &ContractErrors::NothingDelegated);
+        }
+
+        let mut delegators: Vec<Address> = get_delegators(&e, &miner);
+        if let Some(index) = delegators.first_index_of(&owner) {
+            delegators.remove(index);
+        }
+        set_delegators(&e, &miner, &delegators);
+
+        set_delegation(&e, &owner, &miner, &0);
+        set_delegated_out(&e, &owner, &(get_delegated_out(&e, &owner) - amount));
+        set_delegated_in(&e, &miner, &(get_delegated_in(&e, &miner) - amount));
+
+        // The delegation can't be taken from a miner that needs it in the current attempt
+        if !state.is_nuked
+            && get_miner_attempt(&e, &(state.current + 1), &miner).is_some()
+            && !is_stake_enough(
+                &e,
+                &state,
+                &stake_power(&e, &miner, &read_stake_lots(&e, &miner)),
+            )
+        {
+            panic_with_error!(&e, &ContractErrors::StakeBelowRequirement);
+        }
+
+        pump_delegated(&e, &owner);
+        pump_delegated(&e, &miner);
+        pump_core(&e);
+    }
+
+    fn delegation(e: Env, owner: Address, miner: Address) -> u128 {
+        get_delegation(&e, &owner, &miner)
+    }
+
+    fn delegators(e: Env, miner: Address) -> Vec<Address> {
+        get_delegators(&e, &miner)
+    }
+
+    fn set_revenue_share(e: Env, miner: Address, share_bps: u32) {
+        miner.require_auth();
+
+        if share_bps > MAX_REVENUE_SHARE_BPS {
+            panic_with_error!(&e, &ContractErrors::InvalidRevenueShare);
+        }
+
+        set_revenue_share(&e, &miner, &share_bps);
+        pump_delegated(&e, &miner);
+        pump_core(&e);
+    }
+
+    fn revenue_share(e: Env, miner: Address) -> u32 {
+        get_revenue_share(&e, &miner)
+    }
+
+    // Once there are guardians, the set can only be changed with the threshold of the current ones
+    // and never while a nuke is armed, so the admin can't take the cancel away from them
+    fn set_guardians(e: Env, guardians: Vec<Address>, members: Vec<Address>, threshold: u32) {
+        let state: ReactorState = get_state(&e).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::NonDiscovered);
+        });
+        admin(&e, &state).require_auth();
+
+        if get_armed_nuke(&e).is_some() {
+            panic_with_error!(&e, &GuardianErrors::NukeIsArmed);
+        }
+
+        if get_guardians(&e).is_some() {
+            require_guardians(&e, &guardians);
+        }
+
+        if members.len() > MAX_GUARDIANS || threshold == 0 || threshold > members.len() {
+            panic_with_error!(&e, &GuardianErrors::InvalidGuardians);
+        }
+
+        for (i, member) in members.iter().enumerate() {
+            if members.first_index_of(&member) != Some(i as u32) {
+                panic_with_error!(&e, &GuardianErrors::InvalidGuardians);
+            }
+        }
+
+        let guardians: GuardianSet = GuardianSet { members, threshold };
+        set_guardians(&e, &guardians);
+
+        e.events().publish(
+            (symbol_short!("guardian"), symbol_short!("set")),
+            (guardians.members, guardians.threshold),
+        );
+        pump_core(&e);
+    }
+
+    fn guardians(e: Env) -> Option<GuardianSet> {
+        get_guardians(&e)
+    }
+
+    // The nuke needs the threshold of the guardians and it only detonates after NUKE_GRACE_PERIOD,
+    // so miners have time to see it coming (and the guardians to cancel it)
+    fn fkin_nuke_it(e: Env, guardians: Vec<Address>) {
+        let state: ReactorState = get_state(&e).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::NonDiscovered);
+        });
+
+        if state.is_nuked {
+            panic_with_error!(&e, &ContractErrors::TheMineWasNuked);
+        }
+
+        require_guardians(&e, &guardians);
+        arm_nuke(&e);
+        pump_core(&e);
+    }
+
+    fn cancel_nuke(e: Env, guardians: Vec<Address>) {
+        if get_state(&e).is_none() {
+            panic_with_error!(&e, &ContractErrors::NonDiscovered);
+        }
+
+        require_guardians(&e, &guardians);
+
+        let armed: ArmedNuke = get_armed_nuke(&e).unwrap_or_else(|| {
+            panic_with_error!(&e, &GuardianErrors::NukeNotArmed);
+        });
+
+        if e.ledger().timestamp() >= armed.detonates_at {
+            panic_with_error!(&e, &GuardianErrors::GracePeriodIsOver);
+        }
+
+        delete_armed_nuke(&e);
+        e.events().publish(
+            (symbol_short!("nuke"), symbol_short!("canceled")),
+            armed.detonates_at,
+        );
+        pump_core(&e);
+    }
+
+    // Once the grace period is over anyone can detonate the nuke
+    fn detonate_nuke(e: Env) {
+        let mut state: ReactorState = get_state(&e).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::NonDiscovered);
+        });
+
+        let armed: ArmedNuke = get_armed_nuke(&e).unwrap_or_else(|| {
+            panic_with_error!(&e, &GuardianErrors::NukeNotArmed);
+        });
+
+        if e.ledger().timestamp() < armed.detonates_at {
+            panic_with_error!(&e, &GuardianErrors::NukeIsCountingDown);
+        }
+
+        delete_armed_nuke(&e);
+        if !state.is_nuked {
+            nuke(&e, &mut state);
+        }
+
+        e.events().publish(
+            (symbol_short!("nuke"), symbol_short!("detonated")),
+            armed.detonates_at,
+        );
+        pump_core(&e);
+    }
+
+    fn armed_nuke(e: Env) -> Option<ArmedNuke> {
+        get_armed_nuke(&e)
+    }
+
+    // A pause stops new stake and mining during an incident but miners can still take their stake
+    // out, it can be undone unlike the nuke
+    fn pause(e: Env, caller: Address) {
+        let state: ReactorState = get_state(&e).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::NonDiscovered);
+        });
+
+        if state.is_nuked {
+            panic_with_error!(&e, &ContractErrors::TheMineWasNuked);
+        }
+
+        require_role(&e, &state, &Role::Pauser, &caller);
+
+        if get_paused(&e) {
+            panic_with_error!(&e, &ContractErrors::AlreadyPaused);
+        }
+
+        set_paused(&e, &true);
+        e.events()
+            .publish((symbol_short!("pause"), symbol_short!("on")), caller);
+        pump_core(&e);
+    }
+
+    fn unpause(e: Env, caller: Address) {
+        let state: ReactorState = get_state(&e).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::NonDiscovered);
+        });
+
+        if state.is_nuked {
+            panic_with_error!(&e, &ContractErrors::TheMineWasNuked);
+        }
+
+        require_role(&e, &state, &Role::Pauser, &caller);
+
+        if !get_paused(&e) {
+            panic_with_error!(&e, &ContractErrors::NotPaused);
+        }
+
+        set_paused(&e, &false);
+        e.events()
+            .publish((symbol_short!("pause"), symbol_short!("off")), caller);
+        pump_core(&e);
+    }
+
+    fn is_paused(e: Env) -> bool {
+        get_paused(&e)
+    }
+
+    fn is_terminal(e: Env) -> bool {
+        get_state(&e).map(|state| state.is_nuked).unwrap_or(false)
+    }
+
+    // Recovers tokens sent to the reactor by mistake, only the balance over what the reactor owes
+    // can leave it
+    fn sweep(e: Env, token: Address, to: Address, amount: u128) {
+        let state: ReactorState = get_state(&e).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::NonDiscovered);
+        });
+        admin(&e, &state).require_auth();
+
+        if amount == 0 {
+            panic_with_error!(&e, &ContractErrors::InvalidAmount);
+        }
+
+        let owed: u128 = owed_balance(&e, &state, &token).unwrap_or_else(|| {
+            panic_with_error!(&e, &SweepErrors::FcmIsNotTracked);
+        });
+
+        let client = token::Client::new(&e, &token);
+        let balance: u128 = client.balance(&e.current_contract_address()) as u128;
+        if amount > balance.saturating_sub(owed) {
+            panic_with_error!(&e, &SweepErrors::SweepExceedsSurplus);
+        }
+
+        client.transfer(&e.current_contract_address(), &to, &(amount as i128));
+
+        // Whatever the token does on transfers, the reactor must still hold everything it owes
+        if (client.balance(&e.current_contract_address()) as u128) < owed {
+            panic_with_error!(&e, &SweepErrors::AccountingInvariantBroken);
+        }
+
+        e.events()
+            .publish((symbol_short!("sweep"), token), (to, amount));
+        pump_core(&e);
+    }
+
+    fn sweepable(e: Env, token: Address) -> u128 {
+        let state: ReactorState = get_state(&e).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::NonDiscovered);
+        });
+
+        match owed_balance(&e, &state, &token) {
+            Some(owed) => (token::Client::new(&e, &token).balance(&e.current_contract_address())
+                as u128)
+                .saturating_sub(owed),
+            None => 0,
+        }
+    }
+
+    fn set_denied(e: Env, miner: Address, denied: bool) {
+        let state: ReactorState = get_state(&e).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::NonDiscovered);
+        });
+        admin(&e, &state).require_auth();
+
+        set_denied_miner(&e, &miner, &denied);
+
+        let action: Symbol = if denied {
+            symbol_short!("added")
+        } else {
+            symbol_short!("removed")
+        };
+        e.events()
+            .publish((symbol_short!("denylist"), action), miner);
+        pump_core(&e);
+    }
+
+    fn is_denied(e: Env, miner: Address) -> bool {
+        is_denied_miner(&e, &miner)
+    }
+
+    fn set_allowed(e: Env, miner: Address, allowed: bool) {
+        let state: ReactorState = get_state(&e).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::NonDiscovered);
+        });
+        admin(&e, &state).require_auth();
+
+        set_allowed_miner(&e, &miner, &allowed);
+
+        let action: Symbol = if allowed {
+            symbol_short!("added")
+        } else {
+            symbol_short!("removed")
+        };
+        e.events()
+            .publish((symbol_short!("allowlist"), action), miner);
+        pump_core(&e);
+    }
+
+    fn is_allowed(e: Env, miner: Address) -> bool {
+        is_allowed_miner(&e, &miner)
+    }
+
+    // While the allowlist is enabled (like during private test phases) only the allowed miners can
+    // mine, the denylist is always checked
+    fn set_allowlist_enabled(e: Env, enabled: bool) {
+        let state: ReactorState = get_state(&e).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::NonDiscovered);
+        });
+        admin(&e, &state).require_auth();
+
+        set_allowlist_enabled(&e, &enabled);
+
+        let action: Symbol = if enabled {
+            symbol_short!("enabled")
+        } else {
+            symbol_short!("disabled")
+        };
+        e.events()
+            .publish((symbol_short!("allowlist"), action), enabled);
+        pump_core(&e);
+    }
+
+    fn allowlist_enabled(e: Env) -> bool {
+        get_allowlist_enabled(&e)
+    }
+
+    // Stakers can change the parameters of the reactor (or nuke it) without the admin, proposals
+    // are voted for VOTING_PERIOD ledgers and executed PROPOSAL_EXECUTION_DELAY ledgers later
+    fn propose(e: Env, proposer: Address, action: ProposalAction) -> u64 {
+        proposer.require_auth();
+
+        let state: ReactorState = get_state(&e).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::NonDiscovered);
+        });
+
+        if current_voting_power(&e, &proposer) < MIN_PROPOSAL_POWER {
+            panic_with_error!(&e, &GovernanceErrors::NotEnoughVotingPower);
+        }
+
+        // Each proposer can only have one proposal in its voting period, so no one can flood the
+        // voters with proposals
+        let ledger: u32 = e.ledger().sequence();
+        if let Some(last) = get_last_proposal(&e, &proposer) {
+            if ledger < last + PROPOSER_COOLDOWN {
+                panic_with_error!(&e, &GovernanceErrors::ProposerIsCoolingDown);
+            }
+        }
+
+        check_proposal_action(&e, &state, &action);
+
+        let id: u64 = get_proposal_count(&e) + 1;
+        let proposal: Proposal = Proposal {
+            id,
+            proposer: proposer.clone(),
+            action,
+            snapshot_ledger: ledger,
+            snapshot_time: e.ledger().timestamp(),
+            total_power: total_voting_power(&e),
+            voting_ends_at: ledger + VOTING_PERIOD,
+            executable_at: ledger + VOTING_PERIOD + PROPOSAL_EXECUTION_DELAY,
+            votes_for: 0,
+            votes_against: 0,
+            executed: false,
+        };
+        set_proposal(&e, &proposal);
+        set_proposal_count(&e, &id);
+        set_last_proposal(&e, &proposer, &ledger, &PROPOSER_COOLDOWN);
+
+        e.events().publish(
+            (symbol_short!("proposal"), symbol_short!("created")),
+            (id, proposer),
+        );
+        pump_proposal(&e, &id);
+        pump_core(&e);
+
+        id
+    }
+
+    fn vote(e: Env, voter: Address, id: u64, support: bool) {
+        voter.require_auth();
+
+        let mut proposal: Proposal = get_proposal(&e, &id).unwrap_or_else(|| {
+            panic_with_error!(&e, &GovernanceErrors::ProposalNotFound);
+        });
+
+        if e.ledger().sequence() > proposal.voting_ends_at {
+            panic_with_error!(&e, &GovernanceErrors::VotingIsClosed);
+        }
+
+        if get_proposal_vote(&e, &id, &voter).is_some() {
+            panic_with_error!(&e, &GovernanceErrors::AlreadyVoted);
+        }
+
+        let power: u128 = voting_power_before(
+            &e,
+            &voter,
+            &proposal.snapshot_ledger,
+            &proposal.snapshot_time,
+        );
+        if power == 0 {
+            panic_with_error!(&e, &GovernanceErrors::NotEnoughVotingPower);
+        }
+
+        if support {
+            proposal.votes_for += power;
+        } else {
+            proposal.votes_against += power;
+        }
+
+        set_proposal(&e, &proposal);
+        set_proposal_vote(&e, &id, &voter, &support);
+
+        e.events().publish(
+            (symbol_short!("proposal"), symbol_short!("voted")),
+            (id, voter, support, power),
+        );
+        pump_proposal(&e, &id);
+        pump_core(&e);
+    }
+
+    // Anyone can execute a proposal that passed once its delay is over
+    fn execute_proposal(e: Env, id: u64) {
+        let mut state: ReactorState = get_state(&e).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::NonDiscovered);
+        });
+
+        let mut proposal: Proposal = get_proposal(&e, &id).unwrap_or_else(|| {
+            panic_with_error!(&e, &GovernanceErrors::ProposalNotFound);
+        });
+
+        if proposal.executed {
+            panic_with_error!(&e, &GovernanceErrors::ProposalAlreadyExecuted);
+        }
+
+        if e.ledger().sequence() < proposal.executable_at {
+            panic_with_error!(&e, &GovernanceErrors::ProposalIsTimeLocked);
+        }
+
+        let quorum: u128 = proposal.total_power * PROPOSAL_QUORUM_BPS / 10_000;
+        if proposal.votes_for <= proposal.votes_against || proposal.votes_for < quorum {
+            panic_with_error!(&e, &GovernanceErrors::ProposalDefeated);
+        }
+
+        proposal.executed = true;
+        set_proposal(&e, &proposal);
+
+        match proposal.action {
+            ProposalAction::SetDifficulty(difficulty) => {
+                state.difficulty = difficulty;
+                set_state(&e, &state);
+            }
+            ProposalAction::SetEmissionBreaker(window, ceiling, defer) => {
+                configure_emission_breaker(&e, &window, &ceiling, &defer);
+            }
+            ProposalAction::SetRequirementCurve(curve) => {
+                check_requirement_curve(&e, &curve);
+                set_requirement_curve(&e, &curve);
+            }
+            ProposalAction::SetStakingCut(cut_bps) => {
+                check_staking_cut(&e, &cut_bps);
+                set_staking_cut(&e, &cut_bps);
+            }
+            // Stakers get the same countdown (and the guardians the same cancel) as a guardian nuke
+            ProposalAction::Nuke => {
+                if state.is_nuked {
+                    panic_with_error!(&e, &ContractErrors::TheMineWasNuked);
+                }
+                arm_nuke(&e);
+            }
+        }
+
+        e.events()
+            .publish((symbol_short!("proposal"), symbol_short!("executed")), id);
+        pump_proposal(&e, &id);
+        pump_core(&e);
+    }
+
+    fn proposal(e: Env, id: u64) -> Option<Proposal> {
+        get_proposal(&e, &id)
+    }
+
+    fn voting_power(e: Env, account: Address) -> u128 {
+        current_voting_power(&e, &account)
+    }
+
+    // The stake and lock the account had at the ledger, with the power the lock has now
+    fn voting_power_at(e: Env, account: Address, ledger: u32) -> u128 {
+        voting_power_before(&e, &account, &(ledger + 1), &e.ledger().timestamp())
+    }
+
+    fn set_prize_pool(e: Env, tokens: Vec<Address>, drip_bps: u32) {
+        let state: ReactorState = get_state(&e).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::NonDiscovered);
+        });
+        admin(&e, &state).require_auth();
+
+        if tokens.len() > MAX_PRIZE_POOL_TOKENS || drip_bps > MAX_PRIZE_DRIP_BPS {
+            panic_with_error!(&e, &ContractErrors::InvalidPrizePoolConfig);
+        }
+
+        for (i, token) in tokens.iter().enumerate() {
+            if tokens.first_index_of(&token) != Some(i as u32) {
+                panic_with_error!(&e, &ContractErrors::InvalidPrizePoolConfig);
+            }
+        }
+
+        set_prize_pool_config(&e, &PrizePoolConfig { tokens, drip_bps });
+        pump_core(&e);
+    }
+
+    fn deposit_prize(e: Env, caller: Address, token: Address, amount: u128) {
+        caller.require_auth();
+
+        if get_state(&e).is_none() {
+            panic_with_error!(&e, &ContractErrors::NonDiscovered);
+        }
+
+        if amount == 0 {
+            panic_with_error!(&e, &ContractErrors::InvalidAmount);
+        }
+
+        let is_accepted: bool = get_prize_pool_config(&e)
+            .map(|config| config.tokens.contains(&token))
+            .unwrap_or(false);
+
+        if !is_accepted {
+            panic_with_error!(&e, &ContractErrors::TokenNotInPrizePool);
+        }
+
+        token::Client::new(&e, &token).transfer(
+            &caller,
+            &e.current_contract_address(),
+            &(amount as i128),
+        );
+
+        set_prize_pool(&e, &token, &(get_prize_pool(&e, &token) + amount));
+        pump_prize_pool(&e, &token);
+        pump_core(&e);
+    }
+
+    fn withdraw_prize(e: Env, token: Address, to: Address, amount: u128) {
+        let state: ReactorState = get_state(&e).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::NonDiscovered);
+        });
+        admin(&e, &state).require_auth();
+
+        // Donations can only leave the pool through the drip, unless the mine is dead or the
+        // token is no longer part of the pool (otherwise its balance would be stuck forever)
+        let is_accepted: bool = get_prize_pool_config(&e)
+            .map(|config| config.tokens.contains(&token))
+            .unwrap_or(false);
+
+        if !state.is_nuked && is_accepted {
+            panic_with_error!(&e, &ContractErrors::PrizePoolIsLocked);
+        }
+
+        let balance: u128 = get_prize_pool(&e, &token);
+        if amount == 0 || amount > balance {
+            panic_with_error!(&e, &ContractErrors::NotEnoughInPrizePool);
+        }
+
+        token::Client::new(&e, &token).transfer(
+            &e.current_contract_address(),
+            &to,
+            &(amount as i128),
+        );
+
+        set_prize_pool(&e, &token, &(balance - amount));
+        pump_prize_pool(&e, &token);
+        pump_core(&e);
+    }
+
+    fn prize_pool(e: Env, token: Address) -> u128 {
+        get_prize_pool(&e, &token)
+    }
+
+    fn prize_pool_drip(e: Env, token: Address) -> u128 {
+        match get_prize_pool_config(&e) {
+            Some(config) if config.tokens.contains(&token) => {
+                calculate_prize_drip(&get_prize_pool(&e, &token), &config.drip_bps)
+            }
+            _ => 0,
+        }
+    }
+
+    fn burn_for_boost(e: Env, miner: Address, amount: u128) {
+        miner.require_auth();
+
+        let state: ReactorState = get_state(&e).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::NonDiscovered);
+        });
+
+        if state.is_nuked {
+            panic_with_error!(&e, &ContractErrors::TheMineWasNuked);
+        }
+
+        require_not_paused(&e);
+
+        let weight: u128 = amount / BOOST_UNIT;
+        if weight == 0 {
+            panic_with_error!(&e, &ContractErrors::InvalidAmount);
+        }
+
+        // An active boost gets the new weight on top but keeps its expiration, once it expires the
+        // next burn starts a new boost from the current block
+        let mut boost: Boost = match get_boost(&e, &miner) {
+            Some(boost) if boost.expires_at > state.current => boost,
+            _ => Boost {
+                miner: miner.clone(),
+                weight: 0,
+                expires_at: state.current + BOOST_DURATION,
+            },
+        };
+
+        if boost.weight as u128 + weight > MAX_BOOST_WEIGHT as u128 {
+            panic_with_error!(&e, &ContractErrors::BoostTooLarge);
+        }
+
+        token::Client::new(&e, &state.fcm).burn(&miner, &((weight * BOOST_UNIT) as i128));
+
+        boost.weight += weight as u32;
+        set_boost(&e, &boost);
+
+        pump_boost(&e, &miner);
+        pump_core(&e);
+    }
+
+    fn boost(e: Env, miner: Address) -> Option<Boost> {
+        get_boost(&e, &miner)
+    }
+
+    fn set_cooling_period(e: Env, period: u64) {
+        let state: ReactorState = get_state(&e).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::NonDiscovered);
+        });
+        admin(&e, &state).require_auth();
+
+        if period > MAX_COOLING_PERIOD {
+            panic_with_error!(&e, &ContractErrors::InvalidCoolingPeriod);
+        }
+
+        set_cooling_period(&e, &period);
+        pump_core(&e);
+    }
+
+    fn withdraw_cooled(e: Env, miner: Address) {
+        miner.require_auth();
+
+        let state: ReactorState = get_state(&e).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::NonDiscovered);
+        });
+
+        let mut rewards: HotRewards = get_hot_rewards(&e, &miner).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::NothingToWithdraw);
+        });
+
+        cool_down_rewards(&mut rewards, &e.ledger().timestamp());
+
+        if rewards.cooled == 0 {
+            panic_with_error!(&e, &ContractErrors::NothingToWithdraw);
+        }
+
+        token::Client::new(&e, &state.fcm).transfer(
+            &e.current_contract_address(),
+            &miner,
+            &(rewards.cooled as i128),
+        );
+        release_fcm(&e, &rewards.cooled);
+
+        rewards.cooled = 0;
+        if rewards.locked == 0 {
+            delete_hot_rewards(&e, &miner);
+        } else {
+            set_hot_rewards(&e, &rewards);
+            pump_hot_rewards(&e, &miner);
+        }
+
+        pump_core(&e);
+    }
+
+    fn hot_rewards(e: Env, miner: Address) -> Option<HotRewards> {
+        get_hot_rewards(&e, &miner).map(|mut rewards| {
+            cool_down_rewards(&mut rewards, &e.ledger().timestamp());
+            rewards
+        })
+    }
+
+    fn set_auto_compound(e: Env, miner: Address, enabled: bool) {
+        miner.require_auth();
+
+        if get_state(&e).is_none() {
+            panic_with_error!(&e, &ContractErrors::NonDiscovered);
+        }
+
+        set_auto_compound(&e, &miner, &enabled);
+        if enabled {
+            pump_auto_compound(&e, &miner);
+        }
+
+        pump_core(&e);
+    }
+
+    fn auto_compound(e: Env, miner: Address) -> bool {
+        get_auto_compound(&e, &miner)
+    }
+
+    fn set_emission_breaker(e: Env, window: u64, ceiling: u128, defer: bool) {
+        let state: ReactorState = get_state(&e).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::NonDiscovered);
+        });
+        admin(&e, &state).require_auth();
+        configure_emission_breaker(&e, &window, &ceiling, &defer);
+        pump_core(&e);
+    }
+
+    fn emission_breaker(e: Env) -> Option<EmissionBreaker> {
+        get_emission_breaker(&e).map(|mut breaker| {
+            roll_emission_window(&mut breaker, &e.ledger().timestamp());
+            breaker
+        })
+    }
+
+    fn claim_deferred(e: Env, miner: Address) {
+        miner.require_auth();
+
+        let state: ReactorState = get_state(&e).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::NonDiscovered);
+        });
+
+        // Claiming mints new FCM, which stops during an incident and once the mine is nuked
+        if state.is_nuked {
+            panic_with_error!(&e, &ContractErrors::TheMineWasNuked);
+        }
+
+        require_not_paused(&e);
+
+        let deferred: u128 = get_deferred_rewards(&e, &miner);
+        if deferred == 0 {
+            panic_with_error!(&e, &ContractErrors::NothingToWithdraw);
+        }
+
+        // Deferred rewards go through the breaker again, whatever doesn't fit stays deferred even if
+        // the breaker drops the rewards over the ceiling by now, they were already promised
+        let allowed: u128 = match get_emission_breaker(&e) {
+            None => deferred,
+            Some(mut breaker) => take_emission_allowance(&e, &mut breaker, &deferred),
+        };
+        if allowed == 0 {
+            panic_with_error!(&e, &ContractErrors::NothingToWithdraw);
+        }
+
+        set_deferred_rewards(&e, &miner, &(deferred - allowed));
+        if deferred > allowed {
+            pump_deferred_rewards(&e, &miner);
+        }
+
+        // Stakers and delegators get their part of the deferred reward like of any other block reward
+        let allowed: i128 = allowed as i128;
+        let miner_amount: i128 = allowed - feed_staking_rewards(&e, &state, &allowed);
+        if miner_amount > 0 {
+            share_block_reward(&e, &state, &miner, &miner_amount);
+        }
+        pump_core(&e);
+    }
+
+    fn set_staking_cut(e: Env, cut_bps: u32) {
+        let state: ReactorState = get_state(&e).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::NonDiscovered);
+        });
+        admin(&e, &state).require_auth();
+        check_staking_cut(&e, &cut_bps);
+        set_staking_cut(&e, &cut_bps);
+        pump_core(&e);
+    }
+
+    fn staking_cut(e: Env) -> u32 {
+        get_staking_cut(&e)
+    }
+
+    fn total_staked(e: Env) -> u128 {
+        get_total_staked(&e)
+    }
+
+    fn claim_staking_rewards(e: Env, staker: Address) -> u128 {
+        staker.require_auth();
+
+        let state: ReactorState = get_state(&e).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::NonDiscovered);
+        });
+
+        let mut rewards: StakerRewards =
+            sync_staking_rewards(&e, &staker, &read_stake_lots(&e, &staker));
+
+        if rewards.pending == 0 {
+            panic_with_error!(&e, &ContractErrors::NothingToWithdraw);
+        }
+
+        let claimed: u128 = rewards.pending;
+        rewards.pending = 0;
+        set_staker_rewards(&e, &staker, &rewards);
+
+        token::Client::new(&e, &state.fcm).transfer(
+            &e.current_contract_address(),
+            &staker,
+            &(claimed as i128),
+        );
+        release_fcm(&e, &claimed);
+
+        pump_core(&e);
+
+        claimed
+    }
+
+    fn staking_rewards(e: Env, staker: Address) -> u128 {
+        match get_staker_rewards(&e, &staker) {
+            None => 0,
+            Some(mut rewards) => {
+                accrue_staking_rewards(&mut rewards, &get_reward_per_token(&e));
+                rewards.pending
+            }
+        }
+    }
+
+    fn deferred_rewards(e: Env, miner: Address) -> u128 {
+        get_deferred_rewards(&e, &miner)
+    }
+}
+
+pub fn admin(e: &Env, state: &ReactorState) -> Address {
+    get_admin(e).unwrap_or(state.finder.clone())
+}
+
+pub fn grant_role(e: &Env, role: &Role, account: &Address) {
+    set_role_member(e, role, account, true);
+    e.events().publish(
+        (symbol_short!("role"), symbol_short!("granted"), *role),
+        account.clone(),
+    );
+}
+
+// Until a reactor has an admin the finder holds every role, the first time the roles are managed
+// they are written down
+pub fn migrate_roles(e: &Env, state: &ReactorState) {
+    if get_admin(e).is_some() {
+        return;
+    }
+
+    set_admin(e, &state.finder);
+    for role in [Role::DifficultyOperator, Role::Pauser, Role::Upgrader] {
+        grant_role(e, &role, &state.finder);
+    }
+}
+
+pub fn has_role(e: &Env, state: &ReactorState, role: &Role, account: &Address) -> bool {
+    match get_admin(e) {
+        None => *account == state.finder,
+        Some(_) => is_role_member(e, role, account),
+    }
+}
+
+pub fn require_role(e: &Env, state: &ReactorState, role: &Role, caller: &Address) {
+    caller.require_auth();
+
+    if !has_role(e, state, role, caller) {
+        panic_with_error!(e, &ContractErrors::MissingRole);
+    }
+}
+
+// The nuke is the terminal state, a pause doesn't matter anymore after it
+pub fn nuke(e: &Env, state: &mut ReactorState) {
+    state.is_nuked = true;
+    set_state(e, state);
+    set_paused(e, &false);
+}
+
+pub fn check_requirement_curve(e: &Env, curve: &RequirementCurve) {
+    let is_growth_valid: bool = match curve.kind {
+        CurveKind::Exponential => {
+            curve.growth_bps > 0 && curve.growth_bps <= MAX_REQUIREMENT_GROWTH_BPS
+        }
+        _ => curve.growth_bps == 0,
+    };
+
+    if curve.step == 0 || curve.amount > MAX_REQUIREMENT_STEP_AMOUNT || !is_growth_valid {
+        panic_with_error!(e, &ContractErrors::InvalidRequirementCurve);
+    }
+}
+
+pub fn check_staking_cut(e: &Env, cut_bps: &u32) {
+    if *cut_bps > MAX_STAKING_CUT_BPS {
+        panic_with_error!(e, &ContractErrors::InvalidStakingCut);
+    }
+}
+
+// A window of 0 disables the breaker, otherwise it must split evenly in buckets
+pub fn check_emission_breaker(e: &Env, window: &u64, ceiling: &u128) {
+    if *window != 0
+        && (!(MIN_EMISSION_WINDOW..=MAX_EMISSION_WINDOW).contains(window)
+            || !window.is_multiple_of(EMISSION_BUCKETS as u64)
+            || *ceiling == 0)
+    {
+        panic_with_error!(e, &ContractErrors::InvalidEmissionBreaker);
+    }
+}
+
+pub fn configure_emission_breaker(e: &Env, window: &u64, ceiling: &u128, defer: &bool) {
+    check_emission_breaker(e, window, ceiling);
+
+    if *window == 0 {
+        delete_emission_breaker(e);
+        return;
+    }
+
+    let now: u64 = e.ledger().timestamp();
+    let breaker: EmissionBreaker = match get_emission_breaker(e) {
+        // The current window keeps what was already minted in it
+        Some(breaker) if breaker.window == *window => EmissionBreaker {
+            window: *window,
+            ceiling: *ceiling,
+            defer: *defer,
+            ..breaker
+        },
+        // The buckets of another window length can't be reused, what was minted in the old window
+        // is counted in the newest bucket of the new one
+        previous => {
+            let minted: u128 = previous
+                .map(|mut breaker| {
+                    roll_emission_window(&mut breaker, &now);
+                    breaker.minted
+                })
+                .unwrap_or(0);
+
+            let mut buckets: Vec<u128> = Vec::new(e);
+            for _ in 1..EMISSION_BUCKETS {
+                buckets.push_back(0);
+            }
+            buckets.push_back(minted);
+
+            EmissionBreaker {
+                window: *window,
+                ceiling: *ceiling,
+                defer: *defer,
+                bucket_start: now,
+                buckets,
+                minted,
+            }
+        }
+    };
+
+    set_emission_breaker(e, &breaker);
+}
+
+// Invalid proposals are rejected when they are created instead of failing once they passed
+pub fn check_proposal_action(e: &Env, state: &ReactorState, action: &ProposalAction) {
+    match action {
+        ProposalAction::SetDifficulty(_) => {}
+        ProposalAction::SetEmissionBreaker(window, ceiling, _) => {
+            check_emission_breaker(e, window, ceiling)
+        }
+        ProposalAction::SetRequirementCurve(curve) => check_requirement_curve(e, curve),
+        ProposalAction::SetStakingCut(cut_bps) => check_staking_cut(e, cut_bps),
+        ProposalAction::Nuke => {
+            if state.is_nuked {
+                panic_with_error!(e, &ContractErrors::TheMineWasNuked);
+            }
+        }
+    }
+}
+
+// Records the stake and the vote-escrowed lock of the account from the current ledger on,
+// accounts from before the governance existed are registered the first time their stake changes
+// or is bumped
+pub fn checkpoint_voting_power(e: &Env, account: &Address) {
+    let lock: Option<VeLock> = get_ve_lock(e, account);
+    let ledger: u32 = e.ledger().sequence();
+    let checkpoint: VotingCheckpoint = VotingCheckpoint {
+        ledger,
+        staked: total_staked(&get_stake_lots(e, account)),
+        locked: lock.as_ref().map(|lock| lock.amount).unwrap_or(0),
+        unlocks_at: lock.map(|lock| lock.unlocks_at).unwrap_or(0),
+    };
+
+    let mut checkpoints: Vec<VotingCheckpoint> = get_voting_checkpoints(e, account);
+    let previous: Option<VotingCheckpoint> = checkpoints.last();
+    if let Some(last) = &previous {
+        if (last.staked, last.locked, last.unlocks_at)
+            == (checkpoint.staked, checkpoint.locked, checkpoint.unlocks_at)
+        {
+            return;
+        }
+    }
+
+    let now: u64 = e.ledger().timestamp();
+    let mut totals: VotingTotals = get_voting_totals(e);
+    roll_voting_totals(&mut totals, &now);
+    if let Some(last) = previous {
+        totals.staked -= last.staked;
+        remove_voting_lock(&mut totals, &last.locked, &last.unlocks_at, &now);
+    }
+    totals.staked += checkpoint.staked;
+    add_voting_lock(
+        &mut totals,
+        &checkpoint.locked,
+        &checkpoint.unlocks_at,
+        &now,
+    );
+    set_voting_totals(e, &totals);
+
+    // Proposals that can still be voted were created in the last VOTING_PERIOD ledgers, so only
+    // the newest checkpoint before that is needed
+    while checkpoints.len() > 1 && checkpoints.get_unchecked(1).ledger + VOTING_PERIOD < ledger {
+        checkpoints.pop_front();
+    }
+
+    match checkpoints.last() {
+        Some(last) if last.ledger == ledger => checkpoints.set(checkpoints.len() - 1, checkpoint),
+        _ => checkpoints.push_back(checkpoint),
+    }
+
+    set_voting_checkpoints(e, account, &checkpoints);
+    pump_stake(e, account);
+}
+
+// Locks vote with the same decaying power they have for mining, but it ends at the start of the
+// week they unlock in so the totals can drop every expired lock without going through them
+pub fn lock_voting_end(unlocks_at: &u64) -> u64 {
+    unlocks_at - unlocks_at % VOTING_LOCK_WEEK
+}
+
+pub fn checkpoint_power(checkpoint: &VotingCheckpoint, at: &u64) -> u128 {
+    let remaining: u64 = lock_voting_end(&checkpoint.unlocks_at).saturating_sub(*at);
+    checkpoint.staked + checkpoint.locked * remaining as u128 / STAKE_COOLDOWN as u128
+}
+
+// Drops the locks whose voting power already ended
+pub fn roll_voting_totals(totals: &mut VotingTotals, now: &u64) {
+    for (week, amount) in totals.expiries.clone().iter() {
+        if week > *now {
+            break;
+        }
+        totals.locked -= amount;
+        totals.locked_until -= amount * week as u128;
+        totals.expiries.remove(week);
+    }
+}
+
+pub fn add_voting_lock(totals: &mut VotingTotals, locked: &u128, unlocks_at: &u64, now: &u64) {
+    let week: u64 = lock_voting_end(unlocks_at);
+    if *locked == 0 || week <= *now {
+        return;
+    }
+    totals.locked += locked;
+    totals.locked_until += locked * week as u128;
+    totals
+        .expiries
+        .set(week, totals.expiries.get(week).unwrap_or(0) + locked);
+}
+
+// Locks that already lost their voting power were dropped from the totals when they expired
+pub fn remove_voting_lock(totals: &mut VotingTotals, locked: &u128, unlocks_at: &u64, now: &u64) {
+    let week: u64 = lock_voting_end(unlocks_at);
+    if *locked == 0 || week <= *now {
+        return;
+    }
+    totals.locked -= locked;
+    totals.locked_until -= locked * week as u128;
+    let remaining: u128 = totals.expiries.get(week).unwrap_or(0) - locked;
+    if remaining == 0 {
+        totals.expiries.remove(week);
+    } else {
+        totals.expiries.set(week, remaining);
+    }
+}
+
+pub fn total_voting_power(e: &Env) -> u128 {
+    let now: u64 = e.ledger().timestamp();
+    let mut totals: VotingTotals = get_voting_totals(e);
+    roll_voting_totals(&mut totals, &now);
+    totals.staked + (totals.locked_until - totals.locked * now as u128) / STAKE_COOLDOWN as u128
+}
+
+pub fn current_voting_power(e: &Env, account: &Address) -> u128 {
+    get_voting_checkpoints(e, account)
+        .last()
+        .map(|c| checkpoint_power(&c, &e.ledger().timestamp()))
+        .unwrap_or(0)
+}
+
+pub fn voting_power_before(e: &Env, account: &Address, ledger: &u32, at: &u64) -> u128 {
+    get_voting_checkpoints(e, account)
+        .iter()
+        .rev()
+        .find(|c| c.ledger < *ledger)
+        .map(|c| checkpoint_power(&c, at))
+        .unwrap_or(0)
+}
+
+// Once the mine is nuked there is nothing left to protect, every stake is considered cooled down
+// so stakers can leave at once. The same happens while an upgrade is pending, the cooldown is
+// longer than UPGRADE_DELAY and stakers must be able to leave before the new code runs
+pub fn exit_time(e: &Env, state: &ReactorState) -> u64 {
+    if state.is_nuked || get_pending_upgrade(e).is_some() {
+        u64::MAX
+    } else {
+        e.ledger().timestamp()
+    }
+}
+
+// Arms the nuke, it can be detonated by anyone once NUKE_GRACE_PERIOD is over
+pub fn arm_nuke(e: &Env) {
+    if get_armed_nuke(e).is_some() {
+        panic_with_error!(e, &GuardianErrors::NukeAlreadyArmed);
+    }
+
+    let armed: ArmedNuke = ArmedNuke {
+        armed_at: e.ledger().timestamp(),
+        detonates_at: e.ledger().timestamp() + NUKE_GRACE_PERIOD,
+    };
+    set_armed_nuke(e, &armed);
+
+    e.events().publish(
+        (symbol_short!("nuke"), symbol_short!("armed")),
+        armed.detonates_at,
+    );
+}
+
+// Every guardian in the list must sign, and together they must reach the threshold of the set
+pub fn require_guardians(e: &Env, signers: &Vec<Address>) {
+    let guardians: GuardianSet = get_guardians(e).unwrap_or_else(|| {
+        panic_with_error!(e, &GuardianErrors::NoGuardians);
+    });
+
+    for (i, signer) in signers.iter().enumerate() {
+        if !guardians.members.contains(&signer) {
+            panic_with_error!(e, &GuardianErrors::NotAGuardian);
+        }
+
+        if signers.first_index_of(&signer) != Some(i as u32) {
+            panic_with_error!(e, &GuardianErrors::NotEnoughGuardians);
+        }
+
+        signer.require_auth();
+    }
+
+    if signers.len() < guardians.threshold {
+        panic_with_error!(e, &GuardianErrors::NotEnoughGuardians);
+    }
+}
+
+pub fn require_miner_admitted(e: &Env, miner: &Address) {
+    if is_denied_miner(e, miner) {
+        panic_with_error!(e, &ContractErrors::MinerIsDenied);
+    }
+
+    if get_allowlist_enabled(e) {
+        if !is_allowed_miner(e, miner) {
+            panic_with_error!(e, &ContractErrors::MinerIsNotAllowed);
+        }
+        pump_miner_list_entry(e, &StorageKeys::AllowedMiner(miner.clone()));
+    }
+}
+
+pub fn require_not_paused(e: &Env) {
+    if get_paused(e) {
+        panic_with_error!(e, &ContractErrors::TheMineIsPaused);
+    }
+}
+
+pub fn create_block_hash(
+    e: &Env,
+    index: &u64,
+    message: &String,
+    prev_hash: &BytesN<32>,
+    nonce: &u64,
+    miner: &Address,
+) -> BytesN<32> {
+    let mut builder: Bytes = Bytes::new(e);
+    builder.append(&index.to_xdr(e));
+    builder.append(&message.clone().to_xdr(e));
+    builder.append(&prev_hash.clone().to_xdr(e));
+    builder.append(&nonce.to_xdr(e));
+    builder.append(&miner.to_xdr(e));
+
+    e.crypto().keccak256(&builder).to_bytes()
+}
+
+pub fn is_difficulty_correct(hash: &BytesN<32>, difficulty: &u32) -> bool {
+    let hex = encode(hash.to_array());
     let mut total_zeroes: u32 = 0;
 
     for char in hex.chars() {
@@ -323,33 +2565,551 @@ pub fn is_difficulty_correct(hash: &BytesN<32>, difficulty: &u32) -> bool {
 // We could use prng to generate the randomness, but for now will go with this method
 // Can be changed later if we find an issue with it
 pub fn find_winner(e: &Env, attempt: &Attempt) -> u32 {
-    let mut builder: Bytes = Bytes::new(&e);
-    builder.append(&attempt.block.to_xdr(&e));
-    builder.append(&e.ledger().sequence().to_xdr(&e));
-    builder.append(&e.ledger().timestamp().to_xdr(&e));
-    builder.append(&attempt.total_miners.to_xdr(&e));
+    let mut builder: Bytes = Bytes::new(e);
+    builder.append(&attempt.block.to_xdr(e));
+    builder.append(&e.ledger().sequence().to_xdr(e));
+    builder.append(&e.ledger().timestamp().to_xdr(e));
+    builder.append(&attempt.total_miners.to_xdr(e));
     let hash: BytesN<32> = e.crypto().keccak256(&builder).to_bytes();
     (hash.get(16).unwrap() as u32 % (attempt.total_miners)) + 1
 }
 
-pub fn mutate_stake_position(e: &Env, state: &ReactorState, address: &Address) {
-    let mut stake: Stake = get_stake(&e, &address).unwrap_or(Stake {
-        owner: address.clone(),
-        amount: 0,
-        cools_at: 0,
+// Slides the window to the current time, a new bucket is started for each step that passed and the
+// oldest ones leave the window
+pub fn roll_emission_window(breaker: &mut EmissionBreaker, now: &u64) {
+    let step: u64 = breaker.window / EMISSION_BUCKETS as u64;
+    if *now < breaker.bucket_start + step {
+        return;
+    }
+
+    let passed: u64 = (*now - breaker.bucket_start) / step;
+    for _ in 0..passed.min(EMISSION_BUCKETS as u64) {
+        breaker.buckets.pop_front();
+        breaker.buckets.push_back(0);
+    }
+
+    breaker.bucket_start += passed * step;
+    breaker.minted = breaker.buckets.iter().sum();
+}
+
+// Counts as much of the amount as the window still allows as minted, returns that part
+pub fn take_emission_allowance(e: &Env, breaker: &mut EmissionBreaker, amount: &u128) -> u128 {
+    roll_emission_window(breaker, &e.ledger().timestamp());
+
+    let allowed: u128 = (*amount).min(breaker.ceiling.saturating_sub(breaker.minted));
+    let newest: u32 = breaker.buckets.len() - 1;
+    breaker
+        .buckets
+        .set(newest, breaker.buckets.get(newest).unwrap() + allowed);
+    breaker.minted += allowed;
+    set_emission_breaker(e, breaker);
+
+    allowed
+}
+
+// Returns how much of a reward can be minted in the current emission window, the rest is deferred
+// or dropped depending on the breaker configuration
+pub fn apply_emission_breaker(e: &Env, miner: &Address, amount: &i128) -> i128 {
+    let mut breaker: EmissionBreaker = match get_emission_breaker(e) {
+        None => return *amount,
+        Some(breaker) => breaker,
+    };
+
+    let amount: u128 = *amount as u128;
+    let allowed: u128 = take_emission_allowance(e, &mut breaker, &amount);
+
+    if breaker.defer && allowed < amount {
+        let deferred: u128 = get_deferred_rewards(e, miner) + (amount - allowed);
+        set_deferred_rewards(e, miner, &deferred);
+        pump_deferred_rewards(e, miner);
+    }
+
+    allowed as i128
+}
+
+// Miners with delegators share a part of the reward with them, each delegator receives a part
+// proportional to the stake it delegated. Shares are sent as any other block reward
+pub fn share_block_reward(e: &Env, state: &ReactorState, miner: &Address, amount: &i128) {
+    let share_bps: u32 = get_revenue_share(e, miner);
+    let delegated_in: u128 = get_delegated_in(e, miner);
+
+    if share_bps == 0 || delegated_in == 0 {
+        send_block_reward(e, state, miner, amount);
+        return;
+    }
+
+    let shared: u128 = *amount as u128 * share_bps as u128 / MAX_REVENUE_SHARE_BPS as u128;
+    let mut miner_amount: i128 = *amount;
+    for delegator in get_delegators(e, miner).iter() {
+        let part: u128 = shared * get_delegation(e, &delegator, miner) / delegated_in;
+        if part > 0 {
+            pump_delegation(e, &delegator, miner);
+            send_block_reward(e, state, &delegator, &(part as i128));
+            miner_amount -= part as i128;
+        }
+    }
+
+    pump_delegated(e, miner);
+
+    if miner_amount > 0 {
+        send_block_reward(e, state, miner, &miner_amount);
+    }
+}
+
+// Mints the reward of a block, miners with auto compound enabled get it added to their stake. If
+// the cooling mode is enabled the reward is kept hot inside the reactor and the miner needs to
+// wait until it cools down before withdrawing it
+pub fn send_block_reward(e: &Env, state: &ReactorState, miner: &Address, amount: &i128) {
+    if get_auto_compound(e, miner) {
+        pump_auto_compound(e, miner);
+        compound_block_reward(e, state, miner, amount);
+        return;
+    }
+
+    let cooling_period: u64 = get_cooling_period(e);
+
+    if cooling_period == 0 {
+        let result = token::StellarAssetClient::new(e, &state.fcm).try_mint(miner, amount);
+        if let Ok(Ok(())) = result {
+            record_minted(e, amount);
+        }
+        return;
+    }
+
+    let result = token::StellarAssetClient::new(e, &state.fcm)
+        .try_mint(&e.current_contract_address(), amount);
+
+    if let Ok(Ok(())) = result {
+        record_minted(e, amount);
+        hold_fcm(e, &(*amount as u128));
+
+        let now: u64 = e.ledger().timestamp();
+        let mut rewards: HotRewards = get_hot_rewards(e, miner).unwrap_or(HotRewards {
+            owner: miner.clone(),
+            locked: 0,
+            cooled: 0,
+            updated_at: now,
+            cools_at: now,
+        });
+
+        cool_down_rewards(&mut rewards, &now);
+
+        // The new reward is merged with the one still hot, the cooling end is the weighted
+        // average of both so older rewards don't get locked again for the whole period
+        let amount: u128 = *amount as u128;
+        let remaining: u128 = rewards.cools_at.saturating_sub(now) as u128;
+        rewards.cools_at = now
+            + ((rewards.locked * remaining + amount * cooling_period as u128)
+                / (rewards.locked + amount)) as u64;
+        rewards.locked += amount;
+
+        set_hot_rewards(e, &rewards);
+        pump_hot_rewards(e, miner);
+    }
+}
+
+// The reward is added to the stake without touching its cooldown, a miner without a stake starts
+// a new one as if it had staked the reward. With a cooling period the reward can't be withdrawn
+// before it would have cooled as a hot reward, so it goes to a lot that cools after the period
+pub fn compound_block_reward(e: &Env, state: &ReactorState, miner: &Address, amount: &i128) {
+    let result = token::StellarAssetClient::new(e, &state.fcm)
+        .try_mint(&e.current_contract_address(), amount);
+
+    if let Ok(Ok(())) = result {
+        record_minted(e, amount);
+        hold_fcm(e, &(*amount as u128));
+
+        let cooling_period: u64 = get_cooling_period(e);
+        let now: u64 = e.ledger().timestamp();
+        let mut lots: Vec<StakeLot> = read_stake_lots(e, miner);
+        match lots.last() {
+            Some(mut lot) if cooling_period == 0 || lot.cools_at >= now + cooling_period => {
+                lot.amount += *amount as u128;
+                lots.set(lots.len() - 1, lot);
+            }
+            Some(_) => add_stake_lot(
+                &mut lots,
+                StakeLot {
+                    amount: *amount as u128,
+                    cools_at: now + cooling_period,
+                },
+            ),
+            None => lots.push_back(StakeLot {
+                amount: *amount as u128,
+                cools_at: e.ledger().timestamp() + STAKE_COOLDOWN,
+            }),
+        }
+
+        set_stake_lots(e, miner, &lots);
+        sync_staking_rewards(e, miner, &lots);
+        checkpoint_voting_power(e, miner);
+        pump_stake(e, miner);
+    }
+}
+
+// Mints the stakers' cut of a block reward into the reactor and spreads it over the total staked,
+// returns the amount taken from the reward (nothing if there are no stakers)
+pub fn feed_staking_rewards(e: &Env, state: &ReactorState, amount: &i128) -> i128 {
+    let total: u128 = get_total_staked(e);
+    let cut: u128 = *amount as u128 * get_staking_cut(e) as u128 / 10_000;
+
+    if total == 0 || cut == 0 {
+        return 0;
+    }
+
+    let result = token::StellarAssetClient::new(e, &state.fcm)
+        .try_mint(&e.current_contract_address(), &(cut as i128));
+
+    if let Ok(Ok(())) = result {
+        record_minted(e, &(cut as i128));
+        hold_fcm(e, &cut);
+        set_reward_per_token(
+            e,
+            &(get_reward_per_token(e) + cut * REWARD_PER_TOKEN_PRECISION / total),
+        );
+        return cut as i128;
+    }
+
+    0
+}
+
+pub fn accrue_staking_rewards(rewards: &mut StakerRewards, reward_per_token: &u128) {
+    rewards.pending +=
+        rewards.staked * (reward_per_token - rewards.paid_per_token) / REWARD_PER_TOKEN_PRECISION;
+    rewards.paid_per_token = *reward_per_token;
+}
+
+// Accrues the rewards of the staker with its registered stake and registers its current stake,
+// stakers from before the yield existed are registered the first time this is called
+pub fn sync_staking_rewards(e: &Env, staker: &Address, lots: &Vec<StakeLot>) -> StakerRewards {
+    let reward_per_token: u128 = get_reward_per_token(e);
+    let mut rewards: StakerRewards = get_staker_rewards(e, staker).unwrap_or(StakerRewards {
+        staked: 0,
+        paid_per_token: reward_per_token,
+        pending: 0,
     });
 
-    if stake.amount > 0 {
+    accrue_staking_rewards(&mut rewards, &reward_per_token);
+
+    let staked: u128 = total_staked(lots);
+    set_total_staked(e, &(get_total_staked(e) - rewards.staked + staked));
+    rewards.staked = staked;
+
+    set_staker_rewards(e, staker, &rewards);
+    pump_staker_rewards(e, staker);
+
+    rewards
+}
+
+pub fn record_minted(e: &Env, amount: &i128) {
+    set_total_minted(e, &(get_total_minted(e) + *amount as u128));
+}
+
+// Reactors that don't track the FCM they hold yet (until they are migrated) are left untouched
+pub fn hold_fcm(e: &Env, amount: &u128) {
+    if let Some(held) = get_held_fcm(e) {
+        set_held_fcm(e, &(held + amount));
+    }
+}
+
+pub fn release_fcm(e: &Env, amount: &u128) {
+    if let Some(held) = get_held_fcm(e) {
+        set_held_fcm(e, &held.saturating_sub(*amount));
+    }
+}
+
+// What the reactor owes of a token: the prize pool, the stake of the token as a stake asset, the
+// receipts deposited by their holders and, for the FCM, everything held for the users. Untracked
+// FCM owes everything the reactor has
+pub fn owed_balance(e: &Env, state: &ReactorState, token: &Address) -> Option<u128> {
+    let mut owed: u128 = get_prize_pool(e, token) + get_asset_staked(e, token);
+    if get_receipt_token(e).as_ref() == Some(token) {
+        owed += get_receipt_deposits(e);
+    }
+
+    if *token != state.fcm {
+        return Some(owed);
+    }
+
+    get_held_fcm(e).map(|held| owed + held)
+}
+
+// Until the admin sets a curve, for every STAKING_DIVISOR blocks mined miners need to stake one
+// more FCM to keep mining
+pub fn requirement_curve(e: &Env) -> RequirementCurve {
+    get_requirement_curve(e).unwrap_or(RequirementCurve {
+        kind: CurveKind::Stepped,
+        basis: CurveBasis::BlockIndex,
+        step: STAKING_DIVISOR,
+        amount: 1_0000000,
+        growth_bps: 0,
+    })
+}
+
+// The requirement never goes over the max supply, so steep curves can't overflow
+pub fn calculate_stake_requirement(curve: &RequirementCurve, at: &u64) -> u128 {
+    let steps: u128 = (*at / curve.step) as u128;
+
+    let requirement: u128 = match curve.kind {
+        CurveKind::Linear => curve.amount.saturating_mul(*at as u128) / curve.step as u128,
+        CurveKind::Stepped => curve.amount.saturating_mul(steps),
+        CurveKind::Exponential => {
+            // Exponentiation by squaring with the factor in fixed point
+            let mut factor: u128 =
+                GROWTH_PRECISION + GROWTH_PRECISION * curve.growth_bps as u128 / 10_000;
+            let mut result: u128 = curve.amount;
+            let mut exponent: u128 = steps;
+            while exponent > 0 && result < MAX_STAKE_REQUIREMENT {
+                if exponent & 1 == 1 {
+                    result = result.saturating_mul(factor) / GROWTH_PRECISION;
+                }
+                factor = factor.saturating_mul(factor) / GROWTH_PRECISION;
+                exponent >>= 1;
+            }
+            result
+        }
+    };
+
+    requirement.min(MAX_STAKE_REQUIREMENT)
+}
+
+pub fn stake_requirement(e: &Env, state: &ReactorState) -> u128 {
+    let curve: RequirementCurve = requirement_curve(e);
+    let at: u64 = match curve.basis {
+        CurveBasis::BlockIndex => state.current,
+        CurveBasis::MintedUnits => (get_total_minted(e) / 1_0000000) as u64,
+    };
+
+    calculate_stake_requirement(&curve, &at)
+}
+
+pub fn is_stake_enough(e: &Env, state: &ReactorState, staked: &u128) -> bool {
+    *staked >= stake_requirement(e, state)
+}
+
+// A vote-escrowed lock counts as a regular stake while it has STAKE_COOLDOWN left, longer locks
+// weight more (up to ~12 times) and their weight goes down as the unlock time gets closer
+pub fn calculate_ve_power(lock: &VeLock, now: &u64) -> u128 {
+    let remaining: u64 = lock.unlocks_at.saturating_sub(*now);
+    lock.amount * remaining as u128 / STAKE_COOLDOWN as u128
+}
+
+// The stake that counts for a miner: its lots (except the part represented by receipts, those
+// count for whoever deposited them), the receipts it deposited in the reactor, its vote-escrowed
+// lock, its extra stake assets and the stake delegated to it
+pub fn stake_power(e: &Env, miner: &Address, lots: &Vec<StakeLot>) -> u128 {
+    let mut power: u128 = total_staked(lots) + ve_power(e, miner) + asset_power(e, miner);
+
+    if get_receipt_token(e).is_some() {
+        power = power.saturating_sub(get_receipted_stake(e, miner)) + get_receipt_deposit(e, miner);
+    }
+
+    pump_delegated(e, miner);
+    power.saturating_sub(get_delegated_out(e, miner)) + get_delegated_in(e, miner)
+}
+
+// The FCM-equivalent of the extra assets staked by the miner, only accepted assets count
+pub fn asset_power(e: &Env, miner: &Address) -> u128 {
+    get_stake_assets(e)
+        .iter()
+        .map(|asset| {
+            pump_asset_stake_lots(e, miner, &asset.token);
+            total_staked(&get_asset_stake_lots(e, miner, &asset.token)) * asset.weight
+                / STAKE_ASSET_WEIGHT_UNIT
+        })
+        .sum()
+}
+
+pub fn deposit_receipts(e: &Env, holder: &Address, amount: &u128) {
+    set_receipt_deposit(e, holder, &(get_receipt_deposit(e, holder) + amount));
+    set_receipt_deposits(e, &(get_receipt_deposits(e) + amount));
+}
+
+pub fn withdraw_receipts(e: &Env, holder: &Address, amount: &u128) {
+    set_receipt_deposit(e, holder, &(get_receipt_deposit(e, holder) - amount));
+    set_receipt_deposits(e, &(get_receipt_deposits(e) - amount));
+}
+
+// Only the staked FCM not represented by receipts nor already delegated can be delegated
+pub fn delegable_stake(e: &Env, owner: &Address) -> u128 {
+    total_staked(&read_stake_lots(e, owner))
+        .saturating_sub(get_receipted_stake(e, owner))
+        .saturating_sub(get_delegated_out(e, owner))
+}
+
+// Withdrawals take first the FCM staked without receipts, the rest needs the receipts back which
+// are burned by the reactor (the ones deposited in the reactor first, then the ones in the wallet)
+pub fn redeem_receipts(e: &Env, miner: &Address, staked: &u128, amount: &u128) {
+    let receipt: Address = match get_receipt_token(e) {
+        None => return,
+        Some(receipt) => receipt,
+    };
+
+    let receipted: u128 = get_receipted_stake(e, miner);
+    let redeemed: u128 = amount.saturating_sub(staked.saturating_sub(receipted));
+    if redeemed == 0 {
+        return;
+    }
+
+    let from_deposit: u128 = redeemed.min(get_receipt_deposit(e, miner));
+    let from_wallet: u128 = redeemed - from_deposit;
+    if (token::Client::new(e, &receipt).balance(miner) as u128) < from_wallet {
+        panic_with_error!(e, &ContractErrors::NotEnoughReceipts);
+    }
+
+    let receipt_admin = token::StellarAssetClient::new(e, &receipt);
+    if from_deposit > 0 {
+        withdraw_receipts(e, miner, &from_deposit);
+        receipt_admin.clawback(&e.current_contract_address(), &(from_deposit as i128));
+    }
+    if from_wallet > 0 {
+        receipt_admin.clawback(miner, &(from_wallet as i128));
+    }
+
+    set_receipted_stake(e, miner, &(receipted - redeemed));
+    pump_receipted_stake(e, miner);
+}
+
+pub fn ve_power(e: &Env, owner: &Address) -> u128 {
+    match get_ve_lock(e, owner) {
+        None => 0,
+        Some(lock) => {
+            pump_ve_lock(e, owner);
+            calculate_ve_power(&lock, &e.ledger().timestamp())
+        }
+    }
+}
+
+// Taking the stake before it cools down burns a part of it, the penalty of each lot goes down
+// linearly from the whole lot (just staked) to nothing (already cooled down)
+pub fn quote_emergency_unstake(lots: &Vec<StakeLot>, now: &u64) -> UnstakeQuote {
+    let mut quote: UnstakeQuote = UnstakeQuote {
+        amount: 0,
+        penalty: 0,
+    };
+
+    for lot in lots.iter() {
+        let time_left: u64 = lot.cools_at.saturating_sub(*now).min(STAKE_COOLDOWN);
+        let penalty: u128 = lot.amount * time_left as u128 / STAKE_COOLDOWN as u128;
+        quote.penalty += penalty;
+        quote.amount += lot.amount - penalty;
+    }
+
+    quote
+}
+
+// Every read of a stake extends its TTL, so an active miner never loses it to the archival
+pub fn read_stake_lots(e: &Env, miner: &Address) -> Vec<StakeLot> {
+    let lots: Vec<StakeLot> = get_stake_lots(e, miner);
+    pump_stake(e, miner);
+    lots
+}
+
+pub fn total_staked(lots: &Vec<StakeLot>) -> u128 {
+    lots.iter().map(|lot| lot.amount).sum()
+}
+
+// Once a miner reaches the max amount of lots, new deposits are merged into the newest lot
+pub fn add_stake_lot(lots: &mut Vec<StakeLot>, lot: StakeLot) {
+    if lots.len() < MAX_STAKE_LOTS {
+        lots.push_back(lot);
+        return;
+    }
+
+    let mut newest: StakeLot = lots.last().unwrap();
+    newest.amount += lot.amount;
+    newest.cools_at = lot.cools_at;
+    lots.set(lots.len() - 1, newest);
+}
+
+// Moves the part of the hot rewards that already cooled down to the withdrawable balance
+pub fn cool_down_rewards(rewards: &mut HotRewards, now: &u64) {
+    if *now >= rewards.cools_at {
+        rewards.cooled += rewards.locked;
+        rewards.locked = 0;
+    } else if *now > rewards.updated_at {
+        let cooled: u128 = rewards.locked * (*now - rewards.updated_at) as u128
+            / (rewards.cools_at - rewards.updated_at) as u128;
+        rewards.cooled += cooled;
+        rewards.locked -= cooled;
+    }
+
+    rewards.updated_at = rewards.updated_at.max(*now);
+}
+
+// The amount of tickets a miner gets in the attempt of a block, besides the boost the miner gets
+// one extra ticket for each multiple of the requirement (or VE_TICKET_STAKE if higher) locked
+pub fn miner_tickets(e: &Env, state: &ReactorState, block: &u64, miner: &Address) -> u32 {
+    let mut tickets: u32 = 1;
+
+    let ve_tickets: u128 = ve_power(e, miner) / stake_requirement(e, state).max(VE_TICKET_STAKE);
+    tickets += ve_tickets.min(VE_MAX_BONUS_TICKETS as u128) as u32;
+
+    if let Some(boost) = get_boost(e, miner) {
+        if boost.expires_at >= *block {
+            tickets += boost.weight;
+            pump_boost(e, miner);
+        }
+    }
+
+    tickets
+}
+
+pub fn calculate_prize_drip(balance: &u128, drip_bps: &u32) -> u128 {
+    balance * (*drip_bps as u128) / 10_000
+}
+
+// Sends the drip of every token in the prize pool to the winner of a block, a token that fails
+// to transfer keeps its balance so it doesn't block the mining process
+pub fn drip_prize_pool(e: &Env, winner: &Address) {
+    let config: PrizePoolConfig = match get_prize_pool_config(e) {
+        None => return,
+        Some(config) => config,
+    };
+
+    for token in config.tokens.iter() {
+        let balance: u128 = get_prize_pool(e, &token);
+        let drip: u128 = calculate_prize_drip(&balance, &config.drip_bps);
+        if drip == 0 {
+            continue;
+        }
+
+        let result = token::Client::new(e, &token).try_transfer(
+            &e.current_contract_address(),
+            winner,
+            &(drip as i128),
+        );
+
+        if let Ok(Ok(())) = result {
+            set_prize_pool(e, &token, &(balance - drip));
+            pump_prize_pool(e, &token);
+        }
+    }
+}
+
+pub fn mutate_stake_position(e: &Env, state: &ReactorState, address: &Address) {
+    let lots: Vec<StakeLot> = get_stake_lots(e, address);
+    let staked: u128 = total_staked(&lots);
+
+    if staked > 0 {
         if e.prng().gen_range::<u64>(0..=100) < 33 {
-            token::Client::new(&e, &state.fcm)
-                .burn(&e.current_contract_address(), &(stake.amount as i128));
-            delete_stake(&e, &stake.owner);
+            token::Client::new(e, &state.fcm)
+                .burn(&e.current_contract_address(), &(staked as i128));
+            release_fcm(e, &staked);
+            set_stake_lots(e, address, &Vec::new(e));
+            checkpoint_voting_power(e, address);
         } else {
-            pump_stake(&e, &stake.owner);
-            token::StellarAssetClient::new(&e, &state.fcm)
-                .mint(&e.current_contract_address(), &(stake.amount as i128));
-            stake.amount += stake.amount;
-            set_stake(&e, &stake);
+            token::StellarAssetClient::new(e, &state.fcm)
+                .mint(&e.current_contract_address(), &(staked as i128));
+            hold_fcm(e, &staked);
+            let mut mutated_lots: Vec<StakeLot> = Vec::new(e);
+            for mut lot in lots.iter() {
+                lot.amount += lot.amount;
+                mutated_lots.push_back(lot);
+            }
+            set_stake_lots(e, address, &mutated_lots);
+            checkpoint_voting_power(e, address);
+            pump_stake(e, address);
         }
     }
 }
@@ -15,4 +15,85 @@ pub enum ContractErrors {
     NothingToWithdraw = 9,
     StakeIsStillHot = 10,
     NotEnoughStaked = 11,
+    TokenNotInPrizePool = 12,
+    InvalidPrizePoolConfig = 13,
+    PrizePoolIsLocked = 14,
+    NotEnoughInPrizePool = 15,
+    InvalidAmount = 16,
+    BoostTooLarge = 17,
+    InvalidCoolingPeriod = 18,
+    InvalidEmissionBreaker = 19,
+    StakeBelowRequirement = 20,
+    InvalidLockDuration = 21,
+    VeLockAlreadyExists = 22,
+    VeLockExpired = 23,
+    ReceiptTokenAlreadySet = 24,
+    InvalidReceiptToken = 25,
+    NotEnoughReceipts = 26,
+    InvalidDelegation = 27,
+    NotEnoughToDelegate = 28,
+    TooManyDelegators = 29,
+    StakeIsDelegated = 30,
+    NothingDelegated = 31,
+    InvalidRevenueShare = 32,
+    InvalidStakingCut = 33,
+    InvalidStakeAssets = 34,
+    StakeAssetNotAccepted = 35,
+    InvalidRequirementCurve = 36,
+    MissingRole = 37,
+    NoPendingAdmin = 38,
+    UpgradeAlreadyScheduled = 39,
+    NoUpgradeScheduled = 40,
+    UpgradeIsTimeLocked = 41,
+    AlreadyMigrated = 42,
+    NotTheDeployer = 43,
+    ReactorIsNotFcmAdmin = 44,
+    TheMineIsPaused = 45,
+    AlreadyPaused = 46,
+    NotPaused = 47,
+    MinerIsDenied = 48,
+    MinerIsNotAllowed = 49,
+}
+
+// Contract specs allow up to 50 cases per error enum, so newer modules have their own enum with
+// codes that don't overlap with the ones above
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+pub enum GovernanceErrors {
+    NotEnoughVotingPower = 100,
+    ProposalNotFound = 101,
+    VotingIsClosed = 102,
+    AlreadyVoted = 103,
+    ProposalIsTimeLocked = 104,
+    ProposalDefeated = 105,
+    ProposalAlreadyExecuted = 106,
+    ProposerIsCoolingDown = 107,
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+pub enum GuardianErrors {
+    InvalidGuardians = 200,
+    NoGuardians = 201,
+    NotAGuardian = 202,
+    NotEnoughGuardians = 203,
+    NukeAlreadyArmed = 204,
+    NukeNotArmed = 205,
+    NukeIsCountingDown = 206,
+    GracePeriodIsOver = 207,
+    NukeIsArmed = 208,
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+pub enum SweepErrors {
+    FcmIsNotTracked = 300,
+    SweepExceedsSurplus = 301,
+    AccountingInvariantBroken = 302,
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+pub enum DelegationErrors {
+    DelegationTooSmall = 400,
 }
@@ -1,6 +1,6 @@
 #![no_std]
 
-mod contract;
-mod errors;
-mod storage;
+pub mod contract;
+pub mod errors;
+pub mod storage;
 mod tests;
@@ -1,8 +1,18 @@
-use soroban_sdk::{contracttype, Address, BytesN, Env, String};
+use soroban_sdk::{contracttype, Address, BytesN, Env, FromVal, Map, String, TryFromVal, Val, Vec};
 
 const DAY_LEDGER: u32 = 17280;
 
+// Stakes live longer than their 60 days cooldown, so they can't be archived before they cool down
+const STAKE_TTL_THRESHOLD: u32 = DAY_LEDGER * 30;
+const STAKE_TTL: u32 = DAY_LEDGER * 61;
+
+// Version 1 stored the state and the blocks as plain structs, from version 2 they are stored inside
+// a versioned envelope so their layout can change in later versions. Version 3 tracks the FCM the
+// reactor holds for its users
+pub const SCHEMA_VERSION: u32 = 3;
+
 #[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ReactorState {
     // This is the asset that is going to be minted by this contract.
     // This contract must be the admin of the asset.
@@ -21,7 +31,17 @@ pub struct ReactorState {
     pub finder: Address,
 }
 
+// Everything the reactor needs to start, given by its admin when initializing it
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReactorConfig {
+    pub fcm: Address,
+    pub genesis_message: String,
+    pub difficulty: u32,
+}
+
 #[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Block {
     pub index: u64,
     pub message: String,
@@ -34,6 +54,18 @@ pub struct Block {
     pub timestamp: u64,
 }
 
+#[contracttype]
+pub enum VersionedState {
+    V2(ReactorState),
+}
+
+#[contracttype]
+pub enum VersionedBlock {
+    V2(Block),
+}
+
+// Stakes used to be a single record with one cooldown for the whole balance, these records are
+// migrated to `StakeLot`s the first time the stake of the miner is updated
 #[contracttype]
 pub struct Stake {
     pub owner: Address,
@@ -41,9 +73,38 @@ pub struct Stake {
     pub cools_at: u64,
 }
 
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StakeLot {
+    pub amount: u128,
+    pub cools_at: u64,
+}
+
+// Vote-escrowed stake, its weight depends on how long it stays locked
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VeLock {
+    pub owner: Address,
+    pub amount: u128,
+    pub unlocks_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UnstakeQuote {
+    // The amount sent back to the miner
+    pub amount: u128,
+
+    // The amount burned for taking the stake before it cooled down
+    pub penalty: u128,
+}
+
 #[contracttype]
 pub struct Attempt {
     pub block: u64,
+
+    // Every miner takes one position plus the extra tickets from its boost, so this is the
+    // amount of positions taken rather than the amount of miners
     pub total_miners: u32,
 }
 
@@ -54,16 +115,279 @@ pub struct MinerAttempt {
     pub miner: Address,
 }
 
+#[contracttype]
+pub struct Boost {
+    pub miner: Address,
+
+    // Extra tickets the miner gets in every attempt while the boost is active
+    pub weight: u32,
+
+    // The last block index where the boost is active
+    pub expires_at: u64,
+}
+
+#[contracttype]
+pub struct PrizePoolConfig {
+    // Tokens accepted by the pool, a part of each one of them is sent to every block winner
+    pub tokens: Vec<Address>,
+
+    // The fraction (in basis points) of each pool balance that drips out when a block closes
+    pub drip_bps: u32,
+}
+
+#[contracttype]
+pub struct HotRewards {
+    pub owner: Address,
+
+    // Rewards that are still hot, they cool down linearly from `updated_at` until `cools_at`
+    pub locked: u128,
+
+    // Rewards that already cooled down and can be withdrawn
+    pub cooled: u128,
+
+    pub updated_at: u64,
+    pub cools_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Role {
+    DifficultyOperator = 0,
+    Pauser = 1, // Pauses the mine
+    Upgrader = 2,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingUpgrade {
+    pub hash: BytesN<32>,
+    pub executable_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GuardianSet {
+    pub members: Vec<Address>,
+    pub threshold: u32, // Guardians needed to arm or cancel the nuke
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ArmedNuke {
+    pub armed_at: u64,
+    pub detonates_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VotingCheckpoint {
+    pub ledger: u32, // The account had this stake and lock from this ledger on
+    pub staked: u128,
+    pub locked: u128,
+    pub unlocks_at: u64,
+}
+
+// The totals only keep the locks that still have voting power, the ones that lose it in the same
+// week are grouped so they can be dropped once that week starts
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VotingTotals {
+    pub staked: u128,
+    pub locked: u128,
+    pub locked_until: u128, // Sum of each lock multiplied by the week its voting power ends
+    pub expiries: Map<u64, u128>, // -> Locked amount that loses its voting power at that week
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ProposalAction {
+    SetDifficulty(u32),
+    SetEmissionBreaker(u64, u128, bool), // window, ceiling, defer
+    SetRequirementCurve(RequirementCurve),
+    SetStakingCut(u32),
+    Nuke,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Proposal {
+    pub id: u64,
+    pub proposer: Address,
+    pub action: ProposalAction,
+
+    // Votes use the stake and lock the voters had before this ledger (the one the proposal was
+    // created in) with the lock power at the snapshot time, and the quorum is taken from the
+    // total voting power at that moment
+    pub snapshot_ledger: u32,
+    pub snapshot_time: u64,
+    pub total_power: u128,
+
+    pub voting_ends_at: u32, // Last ledger votes are accepted
+    pub executable_at: u32,  // First ledger the proposal can be executed
+    pub votes_for: u128,
+    pub votes_against: u128,
+    pub executed: bool,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CurveKind {
+    Linear = 0,      // `amount` for every `step`, grows continuously
+    Stepped = 1,     // `amount` for every full `step`
+    Exponential = 2, // `amount` grows by `growth_bps` for every full `step`
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CurveBasis {
+    BlockIndex = 0,
+    MintedUnits = 1, // Whole FCM minted as rewards
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RequirementCurve {
+    pub kind: CurveKind,
+    pub basis: CurveBasis,
+    pub step: u64,
+    pub amount: u128,
+    pub growth_bps: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StakeAsset {
+    pub token: Address,
+
+    // FCM-equivalent of one unit of the asset, scaled by STAKE_ASSET_WEIGHT_UNIT
+    pub weight: u128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StakerRewards {
+    // The stake registered for the staker the last time its rewards were updated
+    pub staked: u128,
+
+    // The reward per token already accounted for the staker
+    pub paid_per_token: u128,
+
+    // Rewards accrued and not claimed yet
+    pub pending: u128,
+}
+
+#[contracttype]
+pub struct EmissionBreaker {
+    // The length (in seconds) of each emission window
+    pub window: u64,
+
+    // The max amount of FCM that can be minted as block rewards during a window
+    pub ceiling: u128,
+
+    // If true, rewards over the ceiling are kept so miners can claim them in a later window,
+    // otherwise they are dropped
+    pub defer: bool,
+
+    // The window slides in steps, it's split in buckets that keep what was minted during their part
+    // of the window (oldest first), the newest one started at bucket_start
+    pub bucket_start: u64,
+    pub buckets: Vec<u128>,
+
+    // Minted during the whole window (the sum of the buckets)
+    pub minted: u128,
+}
+
 #[contracttype]
 pub enum StorageKeys {
     MineState,
+    SchemaVersion, // -> u32 Reactors without it use the version 1 layout
+
+    // Reactors discovered before the roles existed don't have an admin, the finder is used instead
+    Admin,
+    PendingAdmin,
+    RoleMember((Role, Address)), // -> bool
+    PendingUpgrade,
+    Guardians, // -> GuardianSet
+
+    // Miners the admin blocked, and the only miners that can mine while the allowlist is enabled
+    DeniedMiner(Address),  // -> bool
+    AllowedMiner(Address), // -> bool
+    AllowlistEnabled,      // -> bool
+    ArmedNuke,
+    Paused, // -> bool Incidents stop the mine for a while, unlike the nuke it can be undone
     Block(u64),
     Stake(Address),
+    StakeLots(Address), // -> Vec<StakeLot> Every deposit of the miner with its own cooldown
+    VeLock(Address),
+
+    // Liquid staking receipts (stFCM), the receipts are minted when staking and burned when un staking
+    ReceiptToken,
+    ReceiptedStake(Address), // -> u128 Part of the miner's stake represented by receipts
+
+    // Holders can delegate their stake to miners, the owner keeps the FCM and its cooldown
+    Delegation((Address, Address)), // -> u128 Stake delegated by the owner (first) to the miner (second)
+    DelegatedOut(Address),          // -> u128 Total stake the owner delegated to miners
+    DelegatedIn(Address),           // -> u128 Total stake the miner received from delegators
+    Delegators(Address),            // -> Vec<Address>
+    RevenueShare(Address), // -> u32 Basis points of the block rewards the miner shares with its delegators
 
     // Attempts and its related values are temporal
     Attempt(u64),
     MinerAttempt((u64, Address)),
     MinerAttemptIndex((u64, u32)), // -> Address This is used to know the position of a miner in an attempt
+
+    PrizePoolConfig,
+    PrizePool(Address), // -> u128 The balance of the pool for the token
+
+    Boost(Address),
+
+    CoolingPeriod, // -> u64 Seconds block rewards take to cool down, 0 means rewards are sent directly
+    HotRewards(Address),
+
+    AutoCompound(Address), // -> bool If true, block rewards are added to the miner's stake
+
+    EmissionBreaker,
+    DeferredRewards(Address), // -> u128 Rewards held back by the emission breaker
+
+    // Staking yield, stakers are registered (and added to the total) the first time they interact
+    StakingCut,     // -> u32 Basis points of each block reward that goes to the stakers
+    TotalStaked,    // -> u128 Sum of the stake registered by every staker
+    RewardPerToken, // -> u128 Accumulated staking rewards per staked unit (scaled)
+    StakerRewards(Address),
+
+    // Extra assets accepted as stake, each one held in its own lots
+    StakeAssets,                        // -> Vec<StakeAsset>
+    AssetStakeLots((Address, Address)), // -> Vec<StakeLot> Lots of the owner (first) for the asset (second)
+    AssetStaked(Address),               // -> u128 Total of the asset held by the reactor as stake
+
+    RequirementCurve,
+    TotalMinted, // -> u128 FCM minted as rewards since the reactor started tracking it
+    HeldFcm, // -> u128 FCM held for the users (stakes, locks and unclaimed rewards), not the prize pool
+
+    // Governance, the voting power of an account is the FCM it has staked and locked in the mine
+    VotingCheckpoints(Address), // -> Vec<VotingCheckpoint>
+    TotalVotingPower,           // -> VotingTotals Sum of the last checkpoint of every account
+    ProposalCount,              // -> u64
+    Proposal(u64),
+    ProposalVote((u64, Address)), // -> bool If the voter (second) supported the proposal (first)
+}
+
+// A contract type can't have more than 50 cases, newer keys live in their own enums. Keys are stored
+// by the name of their case, so the names must not repeat the ones in StorageKeys
+#[contracttype]
+pub enum ReceiptKeys {
+    ReceiptDeposit(Address), // -> u128 Receipts the holder keeps in the reactor, only these count as its stake
+    ReceiptDeposits,         // -> u128 Receipts kept in the reactor for all the holders
+}
+
+#[contracttype]
+pub enum GovernanceKeys {
+    LastProposal(Address), // -> u32 Ledger of the last proposal of the proposer, gone once its cooldown ends
+}
+
+#[contracttype]
+pub enum AssetKeys {
+    OwnerAssets(Address), // -> Vec<Address> Assets the owner has lots of, listed or not, so they can be extended
 }
 
 pub fn pump_core(e: &Env) {
@@ -72,29 +396,66 @@ pub fn pump_core(e: &Env) {
         .extend_ttl(DAY_LEDGER, DAY_LEDGER * 3);
 }
 
+pub fn set_schema_version(e: &Env, version: &u32) {
+    e.storage()
+        .instance()
+        .set(&StorageKeys::SchemaVersion, version);
+}
+
+pub fn get_schema_version(e: &Env) -> u32 {
+    e.storage()
+        .instance()
+        .get(&StorageKeys::SchemaVersion)
+        .unwrap_or(1)
+}
+
 pub fn set_state(e: &Env, state: &ReactorState) {
-    e.storage().instance().set(&StorageKeys::MineState, state);
+    e.storage()
+        .instance()
+        .set(&StorageKeys::MineState, &VersionedState::V2(state.clone()));
 }
 
+// A state in the version 1 layout is rewritten in the current one the first time it's read
 pub fn get_state(e: &Env) -> Option<ReactorState> {
-    e.storage().instance().get(&StorageKeys::MineState)
+    let raw: Val = e.storage().instance().get(&StorageKeys::MineState)?;
+
+    if let Ok(versioned) = VersionedState::try_from_val(e, &raw) {
+        return match versioned {
+            VersionedState::V2(state) => Some(state),
+        };
+    }
+
+    let state: ReactorState = ReactorState::from_val(e, &raw);
+    set_state(e, &state);
+    Some(state)
 }
 
 pub fn set_block(e: &Env, attempt: &Block) {
-    e.storage()
-        .persistent()
-        .set(&StorageKeys::Block(attempt.index.clone()), attempt);
+    e.storage().persistent().set(
+        &StorageKeys::Block(attempt.index),
+        &VersionedBlock::V2(attempt.clone()),
+    );
 }
 
+// There are too many blocks to migrate them at once, like the state they are rewritten in the
+// current layout the first time they are read
 pub fn get_block(e: &Env, index: &u64) -> Option<Block> {
-    e.storage()
-        .persistent()
-        .get(&StorageKeys::Block(index.clone()))
+    let raw: Val = e.storage().persistent().get(&StorageKeys::Block(*index))?;
+
+    if let Ok(versioned) = VersionedBlock::try_from_val(e, &raw) {
+        return match versioned {
+            VersionedBlock::V2(block) => Some(block),
+        };
+    }
+
+    let block: Block = Block::from_val(e, &raw);
+    set_block(e, &block);
+    Some(block)
 }
 
 pub fn pump_block(e: &Env, index: &u64) {
     e.storage().persistent().extend_ttl(
-        &StorageKeys::Block(index.clone()),
+        &StorageKeys::Block(*index),
         DAY_LEDGER * 15,
         DAY_LEDGER * 30,
     );
@@ -118,29 +479,83 @@ pub fn delete_stake(e: &Env, miner: &Address) {
         .remove(&StorageKeys::Stake(miner.clone()));
 }
 
+// If the miner still has a legacy stake, it's returned as a single lot
+pub fn get_stake_lots(e: &Env, miner: &Address) -> Vec<StakeLot> {
+    if let Some(lots) = e
+        .storage()
+        .persistent()
+        .get::<StorageKeys, Vec<StakeLot>>(&StorageKeys::StakeLots(miner.clone()))
+    {
+        return lots;
+    }
+
+    let mut lots: Vec<StakeLot> = Vec::new(e);
+    if let Some(stake) = get_stake(e, miner) {
+        if stake.amount > 0 {
+            lots.push_back(StakeLot {
+                amount: stake.amount,
+                cools_at: stake.cools_at,
+            });
+        }
+    }
+
+    lots
+}
+
+// Saving the lots also removes the legacy stake of the miner, if any
+pub fn set_stake_lots(e: &Env, miner: &Address, lots: &Vec<StakeLot>) {
+    delete_stake(e, miner);
+
+    if lots.is_empty() {
+        e.storage()
+            .persistent()
+            .remove(&StorageKeys::StakeLots(miner.clone()));
+    } else {
+        e.storage()
+            .persistent()
+            .set(&StorageKeys::StakeLots(miner.clone()), lots);
+    }
+}
+
+// Extends the stake and the entries that need to live as long as it
 pub fn pump_stake(e: &Env, miner: &Address) {
-    e.storage().persistent().extend_ttl(
-        &StorageKeys::Stake(miner.clone()),
-        DAY_LEDGER * 15,
-        DAY_LEDGER * 30,
-    );
+    for key in [
+        StorageKeys::StakeLots(miner.clone()),
+        StorageKeys::Stake(miner.clone()),
+        StorageKeys::ReceiptedStake(miner.clone()),
+        StorageKeys::StakerRewards(miner.clone()),
+        StorageKeys::VotingCheckpoints(miner.clone()),
+    ] {
+        if e.storage().persistent().has(&key) {
+            e.storage()
+                .persistent()
+                .extend_ttl(&key, STAKE_TTL_THRESHOLD, STAKE_TTL);
+        }
+    }
+
+    let deposit_key = ReceiptKeys::ReceiptDeposit(miner.clone());
+    if e.storage().persistent().has(&deposit_key) {
+        e.storage()
+            .persistent()
+            .extend_ttl(&deposit_key, STAKE_TTL_THRESHOLD, STAKE_TTL);
+    }
 }
 
 pub fn set_attempt(e: &Env, index: &u64, attempt: &Attempt) {
     e.storage()
         .temporary()
-        .set::<StorageKeys, Attempt>(&StorageKeys::Attempt(index.clone()), attempt);
+        .set::<StorageKeys, Attempt>(&StorageKeys::Attempt(*index), attempt);
 }
 
 pub fn get_attempt(e: &Env, index: &u64) -> Option<Attempt> {
     e.storage()
         .temporary()
-        .get::<StorageKeys, Attempt>(&StorageKeys::Attempt(index.clone()))
+        .get::<StorageKeys, Attempt>(&StorageKeys::Attempt(*index))
 }
 
 pub fn set_miner_attempt(e: &Env, miner_attempt: &MinerAttempt) {
     e.storage().temporary().set::<StorageKeys, MinerAttempt>(
-        &StorageKeys::MinerAttempt((miner_attempt.block.clone(), miner_attempt.miner.clone())),
+        &StorageKeys::MinerAttempt((miner_attempt.block, miner_attempt.miner.clone())),
         miner_attempt,
     );
 }
@@ -148,18 +563,12 @@ pub fn set_miner_attempt(e: &Env, miner_attempt: &MinerAttempt) {
 pub fn get_miner_attempt(e: &Env, block: &u64, miner: &Address) -> Option<MinerAttempt> {
     e.storage()
         .temporary()
-        .get::<StorageKeys, MinerAttempt>(&StorageKeys::MinerAttempt((
-            block.clone(),
-            miner.clone(),
-        )))
+        .get::<StorageKeys, MinerAttempt>(&StorageKeys::MinerAttempt((*block, miner.clone())))
 }
 
 pub fn set_miner_attempt_index(e: &Env, miner_attempt: &MinerAttempt) {
     e.storage().temporary().set::<StorageKeys, Address>(
-        &StorageKeys::MinerAttemptIndex((
-            miner_attempt.block.clone(),
-            miner_attempt.position.clone(),
-        )),
+        &StorageKeys::MinerAttemptIndex((miner_attempt.block, miner_attempt.position)),
         &miner_attempt.miner,
     );
 }
@@ -167,8 +576,799 @@ pub fn set_miner_attempt_index(e: &Env, miner_attempt: &MinerAttempt) {
 pub fn get_miner_attempt_index(e: &Env, block: &u64, position: &u32) -> Option<Address> {
     e.storage()
         .temporary()
-        .get::<StorageKeys, Address>(&StorageKeys::MinerAttemptIndex((
-            block.clone(),
-            position.clone(),
-        )))
+        .get::<StorageKeys, Address>(&StorageKeys::MinerAttemptIndex((*block, *position)))
+}
+
+pub fn set_prize_pool_config(e: &Env, config: &PrizePoolConfig) {
+    e.storage()
+        .instance()
+        .set(&StorageKeys::PrizePoolConfig, config);
+}
+
+pub fn get_prize_pool_config(e: &Env) -> Option<PrizePoolConfig> {
+    e.storage().instance().get(&StorageKeys::PrizePoolConfig)
+}
+
+pub fn set_prize_pool(e: &Env, token: &Address, amount: &u128) {
+    e.storage()
+        .persistent()
+        .set(&StorageKeys::PrizePool(token.clone()), amount);
+}
+
+pub fn get_prize_pool(e: &Env, token: &Address) -> u128 {
+    e.storage()
+        .persistent()
+        .get(&StorageKeys::PrizePool(token.clone()))
+        .unwrap_or(0)
+}
+
+pub fn pump_prize_pool(e: &Env, token: &Address) {
+    e.storage().persistent().extend_ttl(
+        &StorageKeys::PrizePool(token.clone()),
+        DAY_LEDGER * 15,
+        DAY_LEDGER * 30,
+    );
+}
+
+pub fn get_boost(e: &Env, miner: &Address) -> Option<Boost> {
+    e.storage()
+        .persistent()
+        .get(&StorageKeys::Boost(miner.clone()))
+}
+
+pub fn set_boost(e: &Env, boost: &Boost) {
+    e.storage()
+        .persistent()
+        .set(&StorageKeys::Boost(boost.miner.clone()), boost);
+}
+
+pub fn pump_boost(e: &Env, miner: &Address) {
+    e.storage().persistent().extend_ttl(
+        &StorageKeys::Boost(miner.clone()),
+        DAY_LEDGER * 15,
+        DAY_LEDGER * 30,
+    );
+}
+
+pub fn set_cooling_period(e: &Env, period: &u64) {
+    e.storage()
+        .instance()
+        .set(&StorageKeys::CoolingPeriod, period);
+}
+
+pub fn get_cooling_period(e: &Env) -> u64 {
+    e.storage()
+        .instance()
+        .get(&StorageKeys::CoolingPeriod)
+        .unwrap_or(0)
+}
+
+pub fn get_hot_rewards(e: &Env, miner: &Address) -> Option<HotRewards> {
+    e.storage()
+        .persistent()
+        .get(&StorageKeys::HotRewards(miner.clone()))
+}
+
+pub fn set_hot_rewards(e: &Env, rewards: &HotRewards) {
+    e.storage()
+        .persistent()
+        .set(&StorageKeys::HotRewards(rewards.owner.clone()), rewards);
+}
+
+pub fn delete_hot_rewards(e: &Env, miner: &Address) {
+    e.storage()
+        .persistent()
+        .remove(&StorageKeys::HotRewards(miner.clone()));
+}
+
+// Rewards can stay hot as long as a stake cools down, so they need to live as long as one
+pub fn pump_hot_rewards(e: &Env, miner: &Address) {
+    e.storage().persistent().extend_ttl(
+        &StorageKeys::HotRewards(miner.clone()),
+        STAKE_TTL_THRESHOLD,
+        STAKE_TTL,
+    );
+}
+
+pub fn set_auto_compound(e: &Env, miner: &Address, enabled: &bool) {
+    if *enabled {
+        e.storage()
+            .persistent()
+            .set(&StorageKeys::AutoCompound(miner.clone()), enabled);
+    } else {
+        e.storage()
+            .persistent()
+            .remove(&StorageKeys::AutoCompound(miner.clone()));
+    }
+}
+
+pub fn get_auto_compound(e: &Env, miner: &Address) -> bool {
+    e.storage()
+        .persistent()
+        .get(&StorageKeys::AutoCompound(miner.clone()))
+        .unwrap_or(false)
+}
+
+pub fn pump_auto_compound(e: &Env, miner: &Address) {
+    e.storage().persistent().extend_ttl(
+        &StorageKeys::AutoCompound(miner.clone()),
+        DAY_LEDGER * 15,
+        DAY_LEDGER * 30,
+    );
+}
+
+pub fn set_emission_breaker(e: &Env, breaker: &EmissionBreaker) {
+    e.storage()
+        .instance()
+        .set(&StorageKeys::EmissionBreaker, breaker);
+}
+
+pub fn get_emission_breaker(e: &Env) -> Option<EmissionBreaker> {
+    e.storage().instance().get(&StorageKeys::EmissionBreaker)
+}
+
+pub fn delete_emission_breaker(e: &Env) {
+    e.storage().instance().remove(&StorageKeys::EmissionBreaker);
+}
+
+pub fn set_deferred_rewards(e: &Env, miner: &Address, amount: &u128) {
+    if *amount == 0 {
+        e.storage()
+            .persistent()
+            .remove(&StorageKeys::DeferredRewards(miner.clone()));
+    } else {
+        e.storage()
+            .persistent()
+            .set(&StorageKeys::DeferredRewards(miner.clone()), amount);
+    }
+}
+
+pub fn get_deferred_rewards(e: &Env, miner: &Address) -> u128 {
+    e.storage()
+        .persistent()
+        .get(&StorageKeys::DeferredRewards(miner.clone()))
+        .unwrap_or(0)
+}
+
+pub fn pump_deferred_rewards(e: &Env, miner: &Address) {
+    e.storage().persistent().extend_ttl(
+        &StorageKeys::DeferredRewards(miner.clone()),
+        DAY_LEDGER * 15,
+        DAY_LEDGER * 30,
+    );
+}
+
+pub fn get_ve_lock(e: &Env, owner: &Address) -> Option<VeLock> {
+    e.storage()
+        .persistent()
+        .get(&StorageKeys::VeLock(owner.clone()))
+}
+
+pub fn set_ve_lock(e: &Env, lock: &VeLock) {
+    e.storage()
+        .persistent()
+        .set(&StorageKeys::VeLock(lock.owner.clone()), lock);
+}
+
+pub fn delete_ve_lock(e: &Env, owner: &Address) {
+    e.storage()
+        .persistent()
+        .remove(&StorageKeys::VeLock(owner.clone()));
+}
+
+// Locks last longer than a stake cooldown, they are kept alive like the stake lots
+pub fn pump_ve_lock(e: &Env, owner: &Address) {
+    e.storage().persistent().extend_ttl(
+        &StorageKeys::VeLock(owner.clone()),
+        STAKE_TTL_THRESHOLD,
+        STAKE_TTL,
+    );
+}
+
+pub fn set_receipt_token(e: &Env, token: &Address) {
+    e.storage()
+        .instance()
+        .set(&StorageKeys::ReceiptToken, token);
+}
+
+pub fn get_receipt_token(e: &Env) -> Option<Address> {
+    e.storage().instance().get(&StorageKeys::ReceiptToken)
+}
+
+pub fn get_receipted_stake(e: &Env, miner: &Address) -> u128 {
+    e.storage()
+        .persistent()
+        .get(&StorageKeys::ReceiptedStake(miner.clone()))
+        .unwrap_or(0)
+}
+
+pub fn set_receipted_stake(e: &Env, miner: &Address, amount: &u128) {
+    if *amount == 0 {
+        e.storage()
+            .persistent()
+            .remove(&StorageKeys::ReceiptedStake(miner.clone()));
+    } else {
+        e.storage()
+            .persistent()
+            .set(&StorageKeys::ReceiptedStake(miner.clone()), amount);
+    }
+}
+
+pub fn pump_receipted_stake(e: &Env, miner: &Address) {
+    if e.storage()
+        .persistent()
+        .has(&StorageKeys::ReceiptedStake(miner.clone()))
+    {
+        e.storage().persistent().extend_ttl(
+            &StorageKeys::ReceiptedStake(miner.clone()),
+            DAY_LEDGER * 15,
+            DAY_LEDGER * 30,
+        );
+    }
+}
+
+pub fn get_receipt_deposit(e: &Env, holder: &Address) -> u128 {
+    e.storage()
+        .persistent()
+        .get(&ReceiptKeys::ReceiptDeposit(holder.clone()))
+        .unwrap_or(0)
+}
+
+pub fn set_receipt_deposit(e: &Env, holder: &Address, amount: &u128) {
+    if *amount == 0 {
+        e.storage()
+            .persistent()
+            .remove(&ReceiptKeys::ReceiptDeposit(holder.clone()));
+    } else {
+        e.storage()
+            .persistent()
+            .set(&ReceiptKeys::ReceiptDeposit(holder.clone()), amount);
+    }
+}
+
+pub fn get_receipt_deposits(e: &Env) -> u128 {
+    e.storage()
+        .instance()
+        .get(&ReceiptKeys::ReceiptDeposits)
+        .unwrap_or(0)
+}
+
+pub fn set_receipt_deposits(e: &Env, amount: &u128) {
+    e.storage()
+        .instance()
+        .set(&ReceiptKeys::ReceiptDeposits, amount);
+}
+
+pub fn get_delegation(e: &Env, owner: &Address, miner: &Address) -> u128 {
+    e.storage()
+        .persistent()
+        .get(&StorageKeys::Delegation((owner.clone(), miner.clone())))
+        .unwrap_or(0)
+}
+
+pub fn set_delegation(e: &Env, owner: &Address, miner: &Address, amount: &u128) {
+    let key: StorageKeys = StorageKeys::Delegation((owner.clone(), miner.clone()));
+    if *amount == 0 {
+        e.storage().persistent().remove(&key);
+    } else {
+        e.storage().persistent().set(&key, amount);
+    }
+}
+
+pub fn pump_delegation(e: &Env, owner: &Address, miner: &Address) {
+    let key: StorageKeys = StorageKeys::Delegation((owner.clone(), miner.clone()));
+    if e.storage().persistent().has(&key) {
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, DAY_LEDGER * 15, DAY_LEDGER * 30);
+    }
+}
+
+pub fn get_delegated_out(e: &Env, owner: &Address) -> u128 {
+    e.storage()
+        .persistent()
+        .get(&StorageKeys::DelegatedOut(owner.clone()))
+        .unwrap_or(0)
+}
+
+pub fn set_delegated_out(e: &Env, owner: &Address, amount: &u128) {
+    if *amount == 0 {
+        e.storage()
+            .persistent()
+            .remove(&StorageKeys::DelegatedOut(owner.clone()));
+    } else {
+        e.storage()
+            .persistent()
+            .set(&StorageKeys::DelegatedOut(owner.clone()), amount);
+    }
+}
+
+pub fn get_delegated_in(e: &Env, miner: &Address) -> u128 {
+    e.storage()
+        .persistent()
+        .get(&StorageKeys::DelegatedIn(miner.clone()))
+        .unwrap_or(0)
+}
+
+pub fn set_delegated_in(e: &Env, miner: &Address, amount: &u128) {
+    if *amount == 0 {
+        e.storage()
+            .persistent()
+            .remove(&StorageKeys::DelegatedIn(miner.clone()));
+    } else {
+        e.storage()
+            .persistent()
+            .set(&StorageKeys::DelegatedIn(miner.clone()), amount);
+    }
+}
+
+pub fn get_delegators(e: &Env, miner: &Address) -> Vec<Address> {
+    e.storage()
+        .persistent()
+        .get(&StorageKeys::Delegators(miner.clone()))
+        .unwrap_or(Vec::new(e))
+}
+
+pub fn set_delegators(e: &Env, miner: &Address, delegators: &Vec<Address>) {
+    if delegators.is_empty() {
+        e.storage()
+            .persistent()
+            .remove(&StorageKeys::Delegators(miner.clone()));
+    } else {
+        e.storage()
+            .persistent()
+            .set(&StorageKeys::Delegators(miner.clone()), delegators);
+    }
+}
+
+pub fn get_revenue_share(e: &Env, miner: &Address) -> u32 {
+    e.storage()
+        .persistent()
+        .get(&StorageKeys::RevenueShare(miner.clone()))
+        .unwrap_or(0)
+}
+
+pub fn set_revenue_share(e: &Env, miner: &Address, share_bps: &u32) {
+    if *share_bps == 0 {
+        e.storage()
+            .persistent()
+            .remove(&StorageKeys::RevenueShare(miner.clone()));
+    } else {
+        e.storage()
+            .persistent()
+            .set(&StorageKeys::RevenueShare(miner.clone()), share_bps);
+    }
+}
+
+// Extends the delegation totals, the list of delegators and the revenue share of an address
+pub fn pump_delegated(e: &Env, address: &Address) {
+    for key in [
+        StorageKeys::DelegatedOut(address.clone()),
+        StorageKeys::DelegatedIn(address.clone()),
+        StorageKeys::Delegators(address.clone()),
+        StorageKeys::RevenueShare(address.clone()),
+    ] {
+        if e.storage().persistent().has(&key) {
+            e.storage()
+                .persistent()
+                .extend_ttl(&key, DAY_LEDGER * 15, DAY_LEDGER * 30);
+        }
+    }
+}
+
+pub fn set_staking_cut(e: &Env, cut_bps: &u32) {
+    e.storage()
+        .instance()
+        .set(&StorageKeys::StakingCut, cut_bps);
+}
+
+pub fn get_staking_cut(e: &Env) -> u32 {
+    e.storage()
+        .instance()
+        .get(&StorageKeys::StakingCut)
+        .unwrap_or(0)
+}
+
+pub fn set_total_staked(e: &Env, amount: &u128) {
+    e.storage()
+        .instance()
+        .set(&StorageKeys::TotalStaked, amount);
+}
+
+pub fn get_total_staked(e: &Env) -> u128 {
+    e.storage()
+        .instance()
+        .get(&StorageKeys::TotalStaked)
+        .unwrap_or(0)
+}
+
+pub fn set_reward_per_token(e: &Env, reward_per_token: &u128) {
+    e.storage()
+        .instance()
+        .set(&StorageKeys::RewardPerToken, reward_per_token);
+}
+
+pub fn get_reward_per_token(e: &Env) -> u128 {
+    e.storage()
+        .instance()
+        .get(&StorageKeys::RewardPerToken)
+        .unwrap_or(0)
+}
+
+pub fn get_staker_rewards(e: &Env, staker: &Address) -> Option<StakerRewards> {
+    e.storage()
+        .persistent()
+        .get(&StorageKeys::StakerRewards(staker.clone()))
+}
+
+pub fn set_staker_rewards(e: &Env, staker: &Address, rewards: &StakerRewards) {
+    e.storage()
+        .persistent()
+        .set(&StorageKeys::StakerRewards(staker.clone()), rewards);
+}
+
+pub fn pump_staker_rewards(e: &Env, staker: &Address) {
+    e.storage().persistent().extend_ttl(
+        &StorageKeys::StakerRewards(staker.clone()),
+        DAY_LEDGER * 15,
+        DAY_LEDGER * 30,
+    );
+}
+
+pub fn set_stake_assets(e: &Env, assets: &Vec<StakeAsset>) {
+    e.storage()
+        .instance()
+        .set(&StorageKeys::StakeAssets, assets);
+}
+
+pub fn get_stake_assets(e: &Env) -> Vec<StakeAsset> {
+    e.storage()
+        .instance()
+        .get(&StorageKeys::StakeAssets)
+        .unwrap_or(Vec::new(e))
+}
+
+pub fn get_asset_stake_lots(e: &Env, owner: &Address, token: &Address) -> Vec<StakeLot> {
+    e.storage()
+        .persistent()
+        .get(&StorageKeys::AssetStakeLots((owner.clone(), token.clone())))
+        .unwrap_or(Vec::new(e))
+}
+
+pub fn set_asset_stake_lots(e: &Env, owner: &Address, token: &Address, lots: &Vec<StakeLot>) {
+    let key: StorageKeys = StorageKeys::AssetStakeLots((owner.clone(), token.clone()));
+    let mut owner_assets: Vec<Address> = get_owner_assets(e, owner);
+    if lots.is_empty() {
+        e.storage().persistent().remove(&key);
+        if let Some(index) = owner_assets.first_index_of(token) {
+            owner_assets.remove(index);
+        }
+    } else {
+        e.storage().persistent().set(&key, lots);
+        if !owner_assets.contains(token) {
+            owner_assets.push_back(token.clone());
+        }
+    }
+    set_owner_assets(e, owner, &owner_assets);
+}
+
+pub fn get_owner_assets(e: &Env, owner: &Address) -> Vec<Address> {
+    e.storage()
+        .persistent()
+        .get(&AssetKeys::OwnerAssets(owner.clone()))
+        .unwrap_or(Vec::new(e))
+}
+
+pub fn set_owner_assets(e: &Env, owner: &Address, assets: &Vec<Address>) {
+    if assets.is_empty() {
+        e.storage()
+            .persistent()
+            .remove(&AssetKeys::OwnerAssets(owner.clone()));
+    } else {
+        e.storage()
+            .persistent()
+            .set(&AssetKeys::OwnerAssets(owner.clone()), assets);
+    }
+}
+
+pub fn pump_owner_assets(e: &Env, owner: &Address) {
+    let key: AssetKeys = AssetKeys::OwnerAssets(owner.clone());
+    if e.storage().persistent().has(&key) {
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, STAKE_TTL_THRESHOLD, STAKE_TTL);
+    }
+}
+
+pub fn pump_asset_stake_lots(e: &Env, owner: &Address, token: &Address) {
+    let key: StorageKeys = StorageKeys::AssetStakeLots((owner.clone(), token.clone()));
+    if e.storage().persistent().has(&key) {
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, STAKE_TTL_THRESHOLD, STAKE_TTL);
+    }
+}
+
+pub fn get_asset_staked(e: &Env, token: &Address) -> u128 {
+    e.storage()
+        .persistent()
+        .get(&StorageKeys::AssetStaked(token.clone()))
+        .unwrap_or(0)
+}
+
+pub fn set_asset_staked(e: &Env, token: &Address, amount: &u128) {
+    e.storage()
+        .persistent()
+        .set(&StorageKeys::AssetStaked(token.clone()), amount);
+}
+
+pub fn pump_asset_staked(e: &Env, token: &Address) {
+    e.storage().persistent().extend_ttl(
+        &StorageKeys::AssetStaked(token.clone()),
+        DAY_LEDGER * 15,
+        DAY_LEDGER * 30,
+    );
+}
+
+pub fn set_requirement_curve(e: &Env, curve: &RequirementCurve) {
+    e.storage()
+        .instance()
+        .set(&StorageKeys::RequirementCurve, curve);
+}
+
+pub fn get_requirement_curve(e: &Env) -> Option<RequirementCurve> {
+    e.storage().instance().get(&StorageKeys::RequirementCurve)
+}
+
+pub fn set_total_minted(e: &Env, amount: &u128) {
+    e.storage()
+        .instance()
+        .set(&StorageKeys::TotalMinted, amount);
+}
+
+pub fn get_total_minted(e: &Env) -> u128 {
+    e.storage()
+        .instance()
+        .get(&StorageKeys::TotalMinted)
+        .unwrap_or(0)
+}
+
+pub fn set_admin(e: &Env, admin: &Address) {
+    e.storage().instance().set(&StorageKeys::Admin, admin);
+}
+
+pub fn get_admin(e: &Env) -> Option<Address> {
+    e.storage().instance().get(&StorageKeys::Admin)
+}
+
+pub fn set_pending_admin(e: &Env, admin: &Address) {
+    e.storage()
+        .instance()
+        .set(&StorageKeys::PendingAdmin, admin);
+}
+
+pub fn get_pending_admin(e: &Env) -> Option<Address> {
+    e.storage().instance().get(&StorageKeys::PendingAdmin)
+}
+
+pub fn delete_pending_admin(e: &Env) {
+    e.storage().instance().remove(&StorageKeys::PendingAdmin);
+}
+
+pub fn set_role_member(e: &Env, role: &Role, account: &Address, is_member: bool) {
+    let key: StorageKeys = StorageKeys::RoleMember((*role, account.clone()));
+    if is_member {
+        e.storage().instance().set(&key, &true);
+    } else {
+        e.storage().instance().remove(&key);
+    }
+}
+
+pub fn is_role_member(e: &Env, role: &Role, account: &Address) -> bool {
+    e.storage()
+        .instance()
+        .get(&StorageKeys::RoleMember((*role, account.clone())))
+        .unwrap_or(false)
+}
+
+pub fn set_pending_upgrade(e: &Env, upgrade: &PendingUpgrade) {
+    e.storage()
+        .instance()
+        .set(&StorageKeys::PendingUpgrade, upgrade);
+}
+
+pub fn get_pending_upgrade(e: &Env) -> Option<PendingUpgrade> {
+    e.storage().instance().get(&StorageKeys::PendingUpgrade)
+}
+
+pub fn delete_pending_upgrade(e: &Env) {
+    e.storage().instance().remove(&StorageKeys::PendingUpgrade);
+}
+
+pub fn set_paused(e: &Env, paused: &bool) {
+    e.storage().instance().set(&StorageKeys::Paused, paused);
+}
+
+pub fn get_paused(e: &Env) -> bool {
+    e.storage()
+        .instance()
+        .get(&StorageKeys::Paused)
+        .unwrap_or(false)
+}
+
+pub fn get_voting_checkpoints(e: &Env, account: &Address) -> Vec<VotingCheckpoint> {
+    e.storage()
+        .persistent()
+        .get(&StorageKeys::VotingCheckpoints(account.clone()))
+        .unwrap_or(Vec::new(e))
+}
+
+pub fn set_voting_checkpoints(e: &Env, account: &Address, checkpoints: &Vec<VotingCheckpoint>) {
+    e.storage().persistent().set(
+        &StorageKeys::VotingCheckpoints(account.clone()),
+        checkpoints,
+    );
+}
+
+pub fn set_voting_totals(e: &Env, totals: &VotingTotals) {
+    e.storage()
+        .instance()
+        .set(&StorageKeys::TotalVotingPower, totals);
+}
+
+pub fn get_voting_totals(e: &Env) -> VotingTotals {
+    e.storage()
+        .instance()
+        .get(&StorageKeys::TotalVotingPower)
+        .unwrap_or(VotingTotals {
+            staked: 0,
+            locked: 0,
+            locked_until: 0,
+            expiries: Map::new(e),
+        })
+}
+
+pub fn set_proposal_count(e: &Env, count: &u64) {
+    e.storage()
+        .instance()
+        .set(&StorageKeys::ProposalCount, count);
+}
+
+pub fn get_proposal_count(e: &Env) -> u64 {
+    e.storage()
+        .instance()
+        .get(&StorageKeys::ProposalCount)
+        .unwrap_or(0)
+}
+
+pub fn set_proposal(e: &Env, proposal: &Proposal) {
+    e.storage()
+        .persistent()
+        .set(&StorageKeys::Proposal(proposal.id), proposal);
+}
+
+pub fn get_proposal(e: &Env, id: &u64) -> Option<Proposal> {
+    e.storage().persistent().get(&StorageKeys::Proposal(*id))
+}
+
+pub fn set_last_proposal(e: &Env, proposer: &Address, ledger: &u32, cooldown: &u32) {
+    let key: GovernanceKeys = GovernanceKeys::LastProposal(proposer.clone());
+    e.storage().temporary().set(&key, ledger);
+    e.storage()
+        .temporary()
+        .extend_ttl(&key, *cooldown, *cooldown);
+}
+
+pub fn get_last_proposal(e: &Env, proposer: &Address) -> Option<u32> {
+    e.storage()
+        .temporary()
+        .get(&GovernanceKeys::LastProposal(proposer.clone()))
+}
+
+pub fn pump_proposal(e: &Env, id: &u64) {
+    e.storage().persistent().extend_ttl(
+        &StorageKeys::Proposal(*id),
+        DAY_LEDGER * 15,
+        DAY_LEDGER * 30,
+    );
+}
+
+pub fn set_proposal_vote(e: &Env, id: &u64, voter: &Address, support: &bool) {
+    let key: StorageKeys = StorageKeys::ProposalVote((*id, voter.clone()));
+    e.storage().persistent().set(&key, support);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, DAY_LEDGER * 15, DAY_LEDGER * 30);
+}
+
+pub fn get_proposal_vote(e: &Env, id: &u64, voter: &Address) -> Option<bool> {
+    e.storage()
+        .persistent()
+        .get(&StorageKeys::ProposalVote((*id, voter.clone())))
+}
+
+pub fn set_guardians(e: &Env, guardians: &GuardianSet) {
+    e.storage()
+        .instance()
+        .set(&StorageKeys::Guardians, guardians);
+}
+
+pub fn get_guardians(e: &Env) -> Option<GuardianSet> {
+    e.storage().instance().get(&StorageKeys::Guardians)
+}
+
+pub fn set_armed_nuke(e: &Env, nuke: &ArmedNuke) {
+    e.storage().instance().set(&StorageKeys::ArmedNuke, nuke);
+}
+
+pub fn get_armed_nuke(e: &Env) -> Option<ArmedNuke> {
+    e.storage().instance().get(&StorageKeys::ArmedNuke)
+}
+
+pub fn delete_armed_nuke(e: &Env) {
+    e.storage().instance().remove(&StorageKeys::ArmedNuke);
+}
+
+pub fn set_denied_miner(e: &Env, miner: &Address, denied: &bool) {
+    let key: StorageKeys = StorageKeys::DeniedMiner(miner.clone());
+    if *denied {
+        e.storage().persistent().set(&key, denied);
+        pump_miner_list_entry(e, &key);
+    } else {
+        e.storage().persistent().remove(&key);
+    }
+}
+
+pub fn is_denied_miner(e: &Env, miner: &Address) -> bool {
+    e.storage()
+        .persistent()
+        .get(&StorageKeys::DeniedMiner(miner.clone()))
+        .unwrap_or(false)
+}
+
+pub fn set_allowed_miner(e: &Env, miner: &Address, allowed: &bool) {
+    let key: StorageKeys = StorageKeys::AllowedMiner(miner.clone());
+    if *allowed {
+        e.storage().persistent().set(&key, allowed);
+        pump_miner_list_entry(e, &key);
+    } else {
+        e.storage().persistent().remove(&key);
+    }
+}
+
+pub fn is_allowed_miner(e: &Env, miner: &Address) -> bool {
+    e.storage()
+        .persistent()
+        .get(&StorageKeys::AllowedMiner(miner.clone()))
+        .unwrap_or(false)
+}
+
+// Entries of the lists live as long as the stakes, they are also extended every time the miner mines
+pub fn pump_miner_list_entry(e: &Env, key: &StorageKeys) {
+    if e.storage().persistent().has(key) {
+        e.storage()
+            .persistent()
+            .extend_ttl(key, STAKE_TTL_THRESHOLD, STAKE_TTL);
+    }
+}
+
+pub fn set_allowlist_enabled(e: &Env, enabled: &bool) {
+    e.storage()
+        .instance()
+        .set(&StorageKeys::AllowlistEnabled, enabled);
+}
+
+pub fn get_allowlist_enabled(e: &Env) -> bool {
+    e.storage()
+        .instance()
+        .get(&StorageKeys::AllowlistEnabled)
+        .unwrap_or(false)
+}
+
+pub fn set_held_fcm(e: &Env, amount: &u128) {
+    e.storage().instance().set(&StorageKeys::HeldFcm, amount);
+}
+
+pub fn get_held_fcm(e: &Env) -> Option<u128> {
+    e.storage().instance().get(&StorageKeys::HeldFcm)
 }
@@ -1,4 +1,25 @@
+mod test_auto_compound;
+mod test_boost;
+mod test_delegation;
 mod test_discover_mine;
+mod test_emission_breaker;
 mod test_fns;
+mod test_governance;
+mod test_guardians;
+mod test_hot_rewards;
+mod test_liquid_staking;
+mod test_migration;
 mod test_mine_blocks;
+mod test_miner_lists;
+mod test_nuke;
+mod test_pause;
+mod test_prize_pool;
+mod test_requirement_curve;
+mod test_roles;
+mod test_stake_assets;
+mod test_staking;
+mod test_staking_rewards;
+mod test_sweep;
+mod test_upgrades;
 mod test_utils;
+mod test_ve_staking;
@@ -0,0 +1,152 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::{Address as _, Ledger};
+use soroban_sdk::{token, vec, Address, Env, Vec};
+
+use crate::contract::{MAX_COOLING_PERIOD, STAKE_COOLDOWN};
+use crate::errors::ContractErrors;
+use crate::storage::StakeLot;
+use crate::tests::test_utils::{create_test_data, mine_block, start_contract, TestData};
+
+#[test]
+fn test_auto_compound_rewards_into_stake() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let genesis_miner: Address = test_data.genesis_block_miner.clone();
+    let miner: Address = Address::generate(&e);
+    token::StellarAssetClient::new(&e, &test_data.fcm_client.address)
+        .mock_all_auths()
+        .mint(&genesis_miner, &5_0000000);
+
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .stake(&genesis_miner, &5_0000000);
+
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .set_auto_compound(&genesis_miner, &true);
+    assert!(test_data.contract_client.auto_compound(&genesis_miner));
+
+    // The genesis miner also wins the first block, so it gets a second reward later
+    mine_block(&e, &test_data, &genesis_miner);
+
+    assert_eq!(
+        test_data.contract_client.stake_lots(&genesis_miner),
+        vec![
+            &e,
+            StakeLot {
+                amount: 6_0000000,
+                cools_at: STAKE_COOLDOWN,
+            }
+        ]
+    );
+    assert_eq!(test_data.fcm_client.balance(&genesis_miner), 0);
+
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .set_auto_compound(&genesis_miner, &false);
+    assert!(!test_data.contract_client.auto_compound(&genesis_miner));
+
+    mine_block(&e, &test_data, &miner);
+
+    assert_eq!(
+        test_data.contract_client.stake_lots(&genesis_miner).len(),
+        1
+    );
+    assert_eq!(
+        test_data
+            .contract_client
+            .stake_lots(&genesis_miner)
+            .get(0)
+            .unwrap()
+            .amount,
+        6_0000000
+    );
+    assert_eq!(test_data.fcm_client.balance(&genesis_miner), 1_0000000);
+}
+
+#[test]
+fn test_auto_compound_without_stake() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .set_auto_compound(&test_data.genesis_block_miner, &true);
+
+    mine_block(&e, &test_data, &Address::generate(&e));
+
+    assert_eq!(
+        test_data
+            .contract_client
+            .stake_lots(&test_data.genesis_block_miner),
+        vec![
+            &e,
+            StakeLot {
+                amount: 1_0000000,
+                cools_at: 61 + STAKE_COOLDOWN,
+            }
+        ]
+    );
+}
+
+#[test]
+fn test_auto_compound_respects_the_cooling_period() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let genesis_miner: Address = test_data.genesis_block_miner.clone();
+    token::StellarAssetClient::new(&e, &test_data.fcm_client.address)
+        .mock_all_auths()
+        .mint(&genesis_miner, &5_0000000);
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .stake(&genesis_miner, &5_0000000);
+
+    // The stake already cooled down, the compounded rewards must not be withdrawable with it
+    e.ledger().set_timestamp(STAKE_COOLDOWN);
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .set_cooling_period(&MAX_COOLING_PERIOD);
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .set_auto_compound(&genesis_miner, &true);
+
+    mine_block(&e, &test_data, &genesis_miner);
+    let mined_at: u64 = e.ledger().timestamp();
+    mine_block(&e, &test_data, &genesis_miner);
+
+    let lots: Vec<StakeLot> = test_data.contract_client.stake_lots(&genesis_miner);
+    assert_eq!(lots.len(), 3);
+    assert_eq!(lots.get(0).unwrap().amount, 5_0000000);
+    assert_eq!(lots.get(1).unwrap().cools_at, mined_at + MAX_COOLING_PERIOD);
+    assert!(test_data
+        .contract_client
+        .hot_rewards(&genesis_miner)
+        .is_none());
+
+    let still_hot_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_un_stake(&genesis_miner, &5_0000001)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(still_hot_error, ContractErrors::StakeIsStillHot.into());
+
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .un_stake(&genesis_miner, &5_0000000);
+    assert_eq!(test_data.fcm_client.balance(&genesis_miner), 5_0000000);
+}
@@ -0,0 +1,121 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::{token, Address, Env};
+
+use crate::contract::{BOOST_DURATION, BOOST_UNIT};
+use crate::errors::ContractErrors;
+use crate::storage::{
+    get_attempt, get_miner_attempt, get_miner_attempt_index, get_state, set_state, Attempt, Boost,
+    ReactorState,
+};
+use crate::tests::test_utils::{create_test_data, mine_block, start_contract, TestData};
+
+#[test]
+fn test_boost_stacking_and_expiration() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let miner: Address = Address::generate(&e);
+    token::StellarAssetClient::new(&e, &test_data.fcm_client.address)
+        .mock_all_auths()
+        .mint(&miner, &10_0000000);
+
+    let invalid_amount_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_burn_for_boost(&miner, &(BOOST_UNIT - 1))
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(invalid_amount_error, ContractErrors::InvalidAmount.into());
+
+    // Only whole units are burned, the rest stays with the miner
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .burn_for_boost(&miner, &(BOOST_UNIT + 5));
+    assert_eq!(test_data.fcm_client.balance(&miner), 9_0000000);
+
+    let boost: Boost = test_data.contract_client.boost(&miner).unwrap();
+    assert_eq!(boost.weight, 1);
+    assert_eq!(boost.expires_at, BOOST_DURATION);
+
+    // Stacking adds weight but doesn't extend the boost
+    e.as_contract(&test_data.contract_client.address, || {
+        let mut state: ReactorState = get_state(&e).unwrap();
+        state.current = 100;
+        set_state(&e, &state);
+    });
+
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .burn_for_boost(&miner, &(BOOST_UNIT * 2));
+
+    let stacked_boost: Boost = test_data.contract_client.boost(&miner).unwrap();
+    assert_eq!(stacked_boost.weight, 3);
+    assert_eq!(stacked_boost.expires_at, BOOST_DURATION);
+
+    let too_large_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_burn_for_boost(&miner, &(BOOST_UNIT * 2))
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(too_large_error, ContractErrors::BoostTooLarge.into());
+    assert_eq!(test_data.fcm_client.balance(&miner), 7_0000000);
+
+    // Once expired, the next burn starts a new boost
+    e.as_contract(&test_data.contract_client.address, || {
+        let mut state: ReactorState = get_state(&e).unwrap();
+        state.current = BOOST_DURATION;
+        set_state(&e, &state);
+    });
+
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .burn_for_boost(&miner, &(BOOST_UNIT * 2));
+
+    let new_boost: Boost = test_data.contract_client.boost(&miner).unwrap();
+    assert_eq!(new_boost.weight, 2);
+    assert_eq!(new_boost.expires_at, BOOST_DURATION * 2);
+}
+
+#[test]
+fn test_boost_gives_extra_tickets() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let boosted_miner: Address = Address::generate(&e);
+    let miner: Address = Address::generate(&e);
+    token::StellarAssetClient::new(&e, &test_data.fcm_client.address)
+        .mock_all_auths()
+        .mint(&boosted_miner, &(BOOST_UNIT as i128 * 3));
+
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .burn_for_boost(&boosted_miner, &(BOOST_UNIT * 3));
+
+    // The first call closes the genesis attempt, the rest are attempts for the next block
+    mine_block(&e, &test_data, &miner);
+    mine_block(&e, &test_data, &boosted_miner);
+
+    e.as_contract(&test_data.contract_client.address, || {
+        let attempt: Attempt = get_attempt(&e, &2).unwrap();
+        assert_eq!(attempt.total_miners, 4);
+        assert_eq!(
+            get_miner_attempt(&e, &2, &boosted_miner).unwrap().position,
+            1
+        );
+        for position in 1..=4u32 {
+            assert_eq!(
+                get_miner_attempt_index(&e, &2, &position).unwrap(),
+                boosted_miner
+            );
+        }
+    });
+}
@@ -0,0 +1,285 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::{Address as _, Ledger};
+use soroban_sdk::{token, vec, Address, Env, Vec};
+
+use crate::contract::{MAX_DELEGATORS, MIN_DELEGATION, STAKE_COOLDOWN};
+use crate::errors::{ContractErrors, DelegationErrors};
+use crate::storage::{get_block, get_state, set_block, set_state, Block, ReactorState};
+use crate::tests::test_utils::{
+    create_test_data, mine_block, next_block_hash, start_contract, TestData,
+};
+
+#[test]
+fn test_delegated_stake_counts_for_the_miner() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let owner: Address = Address::generate(&e);
+    let miner: Address = Address::generate(&e);
+    token::StellarAssetClient::new(&e, &test_data.fcm_client.address)
+        .mock_all_auths()
+        .mint(&owner, &10_0000000);
+
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .stake(&owner, &10_0000000);
+
+    let self_delegation_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_delegate_stake(&owner, &owner, &8_0000000)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(
+        self_delegation_error,
+        ContractErrors::InvalidDelegation.into()
+    );
+
+    let not_enough_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_delegate_stake(&owner, &miner, &11_0000000)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(not_enough_error, ContractErrors::NotEnoughToDelegate.into());
+
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .delegate_stake(&owner, &miner, &8_0000000);
+    assert_eq!(
+        test_data.contract_client.delegation(&owner, &miner),
+        8_0000000
+    );
+    assert_eq!(
+        test_data.contract_client.delegators(&miner),
+        vec![&e, owner.clone()]
+    );
+    assert_eq!(test_data.contract_client.effective_stake(&owner), 2_0000000);
+    assert_eq!(test_data.contract_client.effective_stake(&miner), 8_0000000);
+
+    // At this index the requirement is 8 FCM
+    e.as_contract(&test_data.contract_client.address, || {
+        let mut state: ReactorState = get_state(&e).unwrap();
+        let mut block: Block = get_block(&e, &state.current).unwrap();
+        state.current = 80_000;
+        block.index = state.current;
+        set_state(&e, &state);
+        set_block(&e, &block);
+    });
+
+    mine_block(&e, &test_data, &miner);
+
+    let (hash, message, nonce) = next_block_hash(&e, &test_data, &owner);
+    let not_enough_staked_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_mine(&hash, &message, &nonce, &owner)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(
+        not_enough_staked_error,
+        ContractErrors::NotEnoughStaked.into()
+    );
+
+    // The owner can only take the stake that is not delegated
+    e.ledger().set_timestamp(STAKE_COOLDOWN + 1);
+    let delegated_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_un_stake(&owner, &3_0000000)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(delegated_error, ContractErrors::StakeIsDelegated.into());
+
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .revoke_delegation(&owner, &miner);
+    assert_eq!(test_data.contract_client.delegation(&owner, &miner), 0);
+    assert_eq!(test_data.contract_client.delegators(&miner), vec![&e]);
+    assert_eq!(test_data.contract_client.effective_stake(&miner), 0);
+
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .un_stake(&owner, &10_0000000);
+    assert_eq!(test_data.fcm_client.balance(&owner), 10_0000000);
+
+    let nothing_delegated_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_revoke_delegation(&owner, &miner)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(
+        nothing_delegated_error,
+        ContractErrors::NothingDelegated.into()
+    );
+}
+
+#[test]
+fn test_revenue_share() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let genesis_miner: Address = test_data.genesis_block_miner.clone();
+    let first_owner: Address = Address::generate(&e);
+    let second_owner: Address = Address::generate(&e);
+    for owner in [first_owner.clone(), second_owner.clone()] {
+        token::StellarAssetClient::new(&e, &test_data.fcm_client.address)
+            .mock_all_auths()
+            .mint(&owner, &4_0000000);
+        test_data
+            .contract_client
+            .mock_all_auths()
+            .stake(&owner, &4_0000000);
+    }
+
+    test_data.contract_client.mock_all_auths().delegate_stake(
+        &first_owner,
+        &genesis_miner,
+        &3_0000000,
+    );
+    test_data.contract_client.mock_all_auths().delegate_stake(
+        &second_owner,
+        &genesis_miner,
+        &1_0000000,
+    );
+
+    let invalid_share_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_set_revenue_share(&genesis_miner, &10_001)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(
+        invalid_share_error,
+        ContractErrors::InvalidRevenueShare.into()
+    );
+
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .set_revenue_share(&genesis_miner, &4_000);
+    assert_eq!(
+        test_data.contract_client.revenue_share(&genesis_miner),
+        4_000
+    );
+
+    // The genesis miner wins the first block, 40% of its reward goes to the delegators
+    mine_block(&e, &test_data, &Address::generate(&e));
+
+    assert_eq!(test_data.fcm_client.balance(&genesis_miner), 6000000);
+    assert_eq!(test_data.fcm_client.balance(&first_owner), 3000000);
+    assert_eq!(test_data.fcm_client.balance(&second_owner), 1000000);
+}
+
+#[test]
+fn test_max_delegators() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let miner: Address = Address::generate(&e);
+    let mut owners: Vec<Address> = Vec::new(&e);
+    for _ in 0..(MAX_DELEGATORS + 1) {
+        owners.push_back(Address::generate(&e));
+    }
+    for owner in owners.iter() {
+        token::StellarAssetClient::new(&e, &test_data.fcm_client.address)
+            .mock_all_auths()
+            .mint(&owner, &1_0000000);
+        test_data
+            .contract_client
+            .mock_all_auths()
+            .stake(&owner, &1_0000000);
+    }
+
+    for owner in owners.iter().take(MAX_DELEGATORS as usize) {
+        test_data
+            .contract_client
+            .mock_all_auths()
+            .delegate_stake(&owner, &miner, &1_0000000);
+    }
+
+    let too_many_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_delegate_stake(&owners.last().unwrap(), &miner, &1_0000000)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(too_many_error, ContractErrors::TooManyDelegators.into());
+}
+
+#[test]
+fn test_delegations_are_locked_during_the_attempt() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let client = test_data.contract_client.mock_all_auths();
+    let owner: Address = Address::generate(&e);
+    let miner: Address = Address::generate(&e);
+    let staker: Address = Address::generate(&e);
+    for address in [owner.clone(), staker.clone()] {
+        token::StellarAssetClient::new(&e, &test_data.fcm_client.address)
+            .mock_all_auths()
+            .mint(&address, &10_0000000);
+        client.stake(&address, &10_0000000);
+    }
+
+    // Dust can't take the slots of the miner
+    let too_small_error = client
+        .try_delegate_stake(&owner, &miner, &(MIN_DELEGATION - 1))
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(too_small_error, DelegationErrors::DelegationTooSmall.into());
+
+    client.delegate_stake(&owner, &miner, &8_0000000);
+
+    // At this index the requirement is 8 FCM
+    e.as_contract(&test_data.contract_client.address, || {
+        let mut state: ReactorState = get_state(&e).unwrap();
+        let mut block: Block = get_block(&e, &state.current).unwrap();
+        state.current = 80_000;
+        block.index = state.current;
+        set_state(&e, &state);
+        set_block(&e, &block);
+    });
+
+    let (hash, message, nonce) = next_block_hash(&e, &test_data, &miner);
+    client.mine(&hash, &message, &nonce, &miner);
+    let (hash, message, nonce) = next_block_hash(&e, &test_data, &staker);
+    client.mine(&hash, &message, &nonce, &staker);
+
+    // The delegation keeps the miner in the current attempt
+    let miner_in_attempt_error = client
+        .try_revoke_delegation(&owner, &miner)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(
+        miner_in_attempt_error,
+        ContractErrors::StakeBelowRequirement.into()
+    );
+
+    // And a miner in the attempt can't delegate the stake it mines with
+    let owner_in_attempt_error = client
+        .try_delegate_stake(&staker, &miner, &3_0000000)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(
+        owner_in_attempt_error,
+        ContractErrors::StakeBelowRequirement.into()
+    );
+    client.delegate_stake(&staker, &miner, &2_0000000);
+
+    // Once the block is closed the delegation can be revoked
+    mine_block(&e, &test_data, &staker);
+    client.revoke_delegation(&owner, &miner);
+    assert_eq!(client.effective_stake(&miner), 2_0000000);
+}
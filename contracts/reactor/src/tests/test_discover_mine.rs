@@ -5,23 +5,22 @@ use soroban_sdk::{Address, BytesN, Env, IntoVal, String};
 
 use crate::contract::create_block_hash;
 use crate::errors::ContractErrors;
-use crate::storage::{get_block, get_state, Block, ReactorState};
-use crate::tests::test_utils::{create_test_data, TestData};
+use crate::storage::{get_block, get_state, Block, ReactorConfig, ReactorState};
+use crate::tests::test_utils::{
+    create_test_data, create_token, deployer_salt, reactor_config, TestData,
+};
 
 #[test]
-fn test_find() {
+fn test_initialize() {
     let e: Env = Env::default();
     let test_data: TestData = create_test_data(&e);
-    let genesis_block_miner: Address = Address::generate(&e);
-    let message: String = String::from_str(&e, "Hello World!");
+    let genesis_block_miner: Address = test_data.genesis_block_miner.clone();
+    let config: ReactorConfig = reactor_config(&e, &test_data);
+    let message: String = config.genesis_message.clone();
 
     assert!(test_data
         .contract_client
-        .try_find(
-            &test_data.fcm_client.address,
-            &genesis_block_miner,
-            &message,
-        )
+        .try_initialize(&genesis_block_miner, &deployer_salt(&e), &config)
         .is_err());
 
     test_data
@@ -30,21 +29,17 @@ fn test_find() {
             address: &genesis_block_miner,
             invoke: &MockAuthInvoke {
                 contract: &test_data.contract_client.address,
-                fn_name: "find",
+                fn_name: "initialize",
                 args: (
-                    test_data.fcm_client.address.clone(),
                     genesis_block_miner.clone(),
-                    message.clone(),
+                    deployer_salt(&e),
+                    config.clone(),
                 )
                     .into_val(&e),
                 sub_invokes: &[],
             },
         }])
-        .find(
-            &test_data.fcm_client.address,
-            &genesis_block_miner,
-            &message,
-        );
+        .initialize(&genesis_block_miner, &deployer_salt(&e), &config);
 
     e.as_contract(&test_data.contract_client.address, || {
         let state: ReactorState = get_state(&e).unwrap();
@@ -93,25 +88,70 @@ fn test_find() {
 fn test_already_discovered_error() {
     let e: Env = Env::default();
     let test_data: TestData = create_test_data(&e);
-    let genesis_block_miner: Address = Address::generate(&e);
-    let message: String = String::from_str(&e, "Hello World!");
+    let config: ReactorConfig = reactor_config(&e, &test_data);
 
-    test_data.contract_client.mock_all_auths().find(
-        &test_data.fcm_client.address,
-        &genesis_block_miner,
-        &message,
+    test_data.contract_client.mock_all_auths().initialize(
+        &test_data.genesis_block_miner,
+        &deployer_salt(&e),
+        &config,
     );
 
     let error = test_data
         .contract_client
         .mock_all_auths()
-        .try_find(
-            &test_data.fcm_client.address,
-            &genesis_block_miner,
-            &message,
-        )
+        .try_initialize(&test_data.genesis_block_miner, &deployer_salt(&e), &config)
         .unwrap_err()
         .unwrap();
 
     assert_eq!(error, ContractErrors::AlreadyDiscovered.into());
 }
+
+#[test]
+fn test_only_the_deployer_initializes() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    let config: ReactorConfig = reactor_config(&e, &test_data);
+
+    let front_runner_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_initialize(&Address::generate(&e), &deployer_salt(&e), &config)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(front_runner_error, ContractErrors::NotTheDeployer.into());
+
+    let wrong_salt_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_initialize(
+            &test_data.genesis_block_miner,
+            &BytesN::from_array(&e, &[1u8; 32]),
+            &config,
+        )
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(wrong_salt_error, ContractErrors::NotTheDeployer.into());
+}
+
+#[test]
+fn test_reactor_must_be_fcm_admin() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+
+    let (other_token, _) = create_token(&e);
+    let error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_initialize(
+            &test_data.genesis_block_miner,
+            &deployer_salt(&e),
+            &ReactorConfig {
+                fcm: other_token.address.clone(),
+                genesis_message: String::from_str(&e, "Hello World!"),
+                difficulty: 0,
+            },
+        )
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(error, ContractErrors::ReactorIsNotFcmAdmin.into());
+}
@@ -0,0 +1,252 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::{Address as _, Ledger};
+use soroban_sdk::{token, Address, Env};
+
+use crate::errors::ContractErrors;
+use crate::storage::EmissionBreaker;
+use crate::tests::test_utils::{create_test_data, mine_block, start_contract, TestData};
+
+#[test]
+fn test_emission_breaker_defers_rewards() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .set_emission_breaker(&3600, &1_0000000, &true);
+
+    let first_miner: Address = Address::generate(&e);
+    let second_miner: Address = Address::generate(&e);
+
+    mine_block(&e, &test_data, &first_miner);
+    assert_eq!(
+        test_data.fcm_client.balance(&test_data.genesis_block_miner),
+        1_0000000
+    );
+
+    // The ceiling was reached, so the reward of the first miner is deferred
+    mine_block(&e, &test_data, &second_miner);
+    assert_eq!(test_data.fcm_client.balance(&first_miner), 0);
+    assert_eq!(
+        test_data.contract_client.deferred_rewards(&first_miner),
+        1_0000000
+    );
+
+    let breaker: EmissionBreaker = test_data.contract_client.emission_breaker().unwrap();
+    assert_eq!(breaker.bucket_start, 0);
+    assert_eq!(breaker.minted, breaker.ceiling);
+
+    let tripped_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_claim_deferred(&first_miner)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(tripped_error, ContractErrors::NothingToWithdraw.into());
+
+    e.ledger().set_timestamp(3600);
+
+    let rolled_breaker: EmissionBreaker = test_data.contract_client.emission_breaker().unwrap();
+    assert_eq!(rolled_breaker.bucket_start, 3600);
+    assert_eq!(rolled_breaker.minted, 0);
+
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .claim_deferred(&first_miner);
+    assert_eq!(test_data.fcm_client.balance(&first_miner), 1_0000000);
+    assert_eq!(test_data.contract_client.deferred_rewards(&first_miner), 0);
+}
+
+#[test]
+fn test_emission_breaker_drops_rewards() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let invalid_breaker_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_set_emission_breaker(&60, &1_0000000, &false)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(
+        invalid_breaker_error,
+        ContractErrors::InvalidEmissionBreaker.into()
+    );
+
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .set_emission_breaker(&3600, &1_0000000, &false);
+
+    let first_miner: Address = Address::generate(&e);
+    mine_block(&e, &test_data, &first_miner);
+    mine_block(&e, &test_data, &Address::generate(&e));
+
+    assert_eq!(test_data.fcm_client.balance(&first_miner), 0);
+    assert_eq!(test_data.contract_client.deferred_rewards(&first_miner), 0);
+
+    // Disabling the breaker lets the rewards flow again
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .set_emission_breaker(&0, &0, &false);
+    assert!(test_data.contract_client.emission_breaker().is_none());
+}
+
+#[test]
+fn test_emission_window_slides() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .set_emission_breaker(&3600, &1_0000000, &true);
+
+    // The ceiling is reached at the end of the first hour, the first block took 57 minutes to be
+    // found so its whole reward is deferred
+    let first_miner: Address = Address::generate(&e);
+    e.ledger().set_timestamp(3400);
+    mine_block(&e, &test_data, &first_miner);
+    mine_block(&e, &test_data, &Address::generate(&e));
+    assert_eq!(
+        test_data.contract_client.deferred_rewards(&first_miner),
+        57_0000000
+    );
+
+    // A new hour doesn't reset the breaker, the reward is still inside the window
+    e.ledger().set_timestamp(3700);
+    let breaker: EmissionBreaker = test_data.contract_client.emission_breaker().unwrap();
+    assert_eq!(breaker.bucket_start, 3600);
+    assert_eq!(breaker.minted, 1_0000000);
+    let still_tripped_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_claim_deferred(&first_miner)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(
+        still_tripped_error,
+        ContractErrors::NothingToWithdraw.into()
+    );
+
+    // Once the bucket with the reward leaves the window the ceiling is free again
+    e.ledger().set_timestamp(2700 + 3600);
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .claim_deferred(&first_miner);
+    assert_eq!(test_data.fcm_client.balance(&first_miner), 1_0000000);
+}
+
+#[test]
+fn test_deferred_rewards_survive_dropping_mode() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .set_emission_breaker(&3600, &1_0000000, &true);
+
+    // The miner is the only one in the next two attempts, both of its rewards are deferred
+    let miner: Address = Address::generate(&e);
+    mine_block(&e, &test_data, &miner);
+    mine_block(&e, &test_data, &miner);
+    mine_block(&e, &test_data, &Address::generate(&e));
+    assert_eq!(
+        test_data.contract_client.deferred_rewards(&miner),
+        2_0000000
+    );
+
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .set_emission_breaker(&3600, &1_0000000, &false);
+
+    // Only one reward fits in the window, the other one is still deferred
+    e.ledger().set_timestamp(3600 * 2);
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .claim_deferred(&miner);
+    assert_eq!(test_data.fcm_client.balance(&miner), 1_0000000);
+    assert_eq!(
+        test_data.contract_client.deferred_rewards(&miner),
+        1_0000000
+    );
+
+    e.ledger().set_timestamp(3600 * 4);
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .claim_deferred(&miner);
+    assert_eq!(test_data.fcm_client.balance(&miner), 2_0000000);
+    assert_eq!(test_data.contract_client.deferred_rewards(&miner), 0);
+}
+
+#[test]
+fn test_deferred_rewards_are_shared() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let client = test_data.contract_client.mock_all_auths();
+    let miner: Address = Address::generate(&e);
+    let owner: Address = Address::generate(&e);
+    token::StellarAssetClient::new(&e, &test_data.fcm_client.address)
+        .mock_all_auths()
+        .mint(&owner, &1_0000000);
+    client.stake(&owner, &1_0000000);
+    client.delegate_stake(&owner, &miner, &1_0000000);
+    client.set_revenue_share(&miner, &5_000);
+
+    client.set_emission_breaker(&3600, &1_0000000, &true);
+    mine_block(&e, &test_data, &miner);
+    mine_block(&e, &test_data, &Address::generate(&e));
+    assert_eq!(client.deferred_rewards(&miner), 1_0000000);
+
+    // The delegators get their share once the reward is claimed
+    e.ledger().set_timestamp(3600);
+    client.claim_deferred(&miner);
+    assert_eq!(test_data.fcm_client.balance(&miner), 5000000);
+    assert_eq!(test_data.fcm_client.balance(&owner), 5000000);
+}
+
+#[test]
+fn test_deferred_rewards_pay_the_staking_cut() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let client = test_data.contract_client.mock_all_auths();
+    let miner: Address = Address::generate(&e);
+    let owner: Address = Address::generate(&e);
+    token::StellarAssetClient::new(&e, &test_data.fcm_client.address)
+        .mock_all_auths()
+        .mint(&owner, &1_0000000);
+    client.stake(&owner, &1_0000000);
+    client.delegate_stake(&owner, &miner, &1_0000000);
+    client.set_revenue_share(&miner, &5_000);
+    client.set_staking_cut(&1_000);
+
+    client.set_emission_breaker(&3600, &1_0000000, &true);
+    mine_block(&e, &test_data, &miner);
+    mine_block(&e, &test_data, &Address::generate(&e));
+    assert_eq!(client.deferred_rewards(&miner), 1_0000000);
+
+    // The stakers already got their cut of the genesis reward, the one deferred pays it on claim
+    assert_eq!(client.staking_rewards(&owner), 1000000);
+    e.ledger().set_timestamp(3600);
+    client.claim_deferred(&miner);
+    assert_eq!(client.staking_rewards(&owner), 2000000);
+    assert_eq!(test_data.fcm_client.balance(&miner), 4500000);
+    assert_eq!(test_data.fcm_client.balance(&owner), 4500000);
+}
@@ -16,5 +16,5 @@ fn test_is_difficulty_correct() {
         ],
     );
 
-    assert_eq!(is_difficulty_correct(&bytes, &6), true);
+    assert!(is_difficulty_correct(&bytes, &6));
 }
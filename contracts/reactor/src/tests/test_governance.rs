@@ -0,0 +1,296 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::{Address as _, Events, Ledger};
+use soroban_sdk::{symbol_short, token, vec, Address, Env, IntoVal};
+
+use crate::contract::{
+    NUKE_GRACE_PERIOD, PROPOSAL_EXECUTION_DELAY, STAKE_COOLDOWN, VE_MIN_LOCK, VOTING_LOCK_WEEK,
+    VOTING_PERIOD,
+};
+use crate::errors::{ContractErrors, GovernanceErrors, GuardianErrors};
+use crate::storage::{get_state, set_stake, ProposalAction, Stake};
+use crate::tests::test_utils::{create_test_data, start_contract, TestData};
+
+// Governance runs for days, entries must live long enough without anyone bumping them
+fn create_long_lived_env() -> Env {
+    let e: Env = Env::default();
+    e.ledger().with_mut(|ledger| {
+        ledger.sequence_number = 100;
+        ledger.min_persistent_entry_ttl = 17280 * 60;
+        ledger.max_entry_ttl = 17280 * 365;
+    });
+    e
+}
+
+fn pass_ledgers(e: &Env, ledgers: u32) {
+    e.ledger().with_mut(|ledger| {
+        ledger.sequence_number += ledgers;
+        ledger.timestamp += ledgers as u64 * 5;
+    });
+}
+
+fn create_staker(e: &Env, test_data: &TestData, amount: i128) -> Address {
+    let staker: Address = Address::generate(e);
+    token::StellarAssetClient::new(e, &test_data.fcm_client.address)
+        .mock_all_auths()
+        .mint(&staker, &amount);
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .stake(&staker, &(amount as u128));
+    staker
+}
+
+#[test]
+fn test_voting_power_checkpoints() {
+    let e: Env = create_long_lived_env();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let start: u32 = e.ledger().sequence();
+    let staker: Address = create_staker(&e, &test_data, 10_0000000);
+    let client = test_data.contract_client.mock_all_auths();
+    assert_eq!(client.voting_power(&staker), 10_0000000);
+
+    // Vote-escrowed locks count with the same decaying power they have for mining, this one
+    // unlocks at the start of a week so it keeps its voting power until the end
+    pass_ledgers(&e, 10);
+    token::StellarAssetClient::new(&e, &test_data.fcm_client.address)
+        .mock_all_auths()
+        .mint(&staker, &5_0000000);
+    let locked_at: u64 = VOTING_LOCK_WEEK * 18 - STAKE_COOLDOWN * 2;
+    e.ledger().set_timestamp(locked_at);
+    client.ve_lock(&staker, &5_0000000, &(STAKE_COOLDOWN * 2));
+    assert_eq!(client.voting_power(&staker), 20_0000000);
+
+    pass_ledgers(&e, 10);
+    e.ledger().set_timestamp(locked_at + STAKE_COOLDOWN);
+    client.un_stake(&staker, &10_0000000);
+    assert_eq!(client.voting_power(&staker), 5_0000000);
+
+    // Past checkpoints keep the stake and the lock, the lock with the power it has now
+    assert_eq!(client.voting_power_at(&staker, &(start - 1)), 0);
+    assert_eq!(client.voting_power_at(&staker, &start), 10_0000000);
+    assert_eq!(client.voting_power_at(&staker, &(start + 9)), 10_0000000);
+    assert_eq!(client.voting_power_at(&staker, &(start + 10)), 15_0000000);
+    assert_eq!(client.voting_power_at(&staker, &(start + 20)), 5_0000000);
+
+    // Extending the lock is recorded too
+    pass_ledgers(&e, 10);
+    client.ve_extend(&staker, &(STAKE_COOLDOWN * 2));
+    assert!(client.voting_power(&staker) > 5_0000000);
+
+    // Stakes from before the governance get their power once they are bumped
+    let legacy_staker: Address = Address::generate(&e);
+    e.as_contract(&test_data.contract_client.address, || {
+        set_stake(
+            &e,
+            &Stake {
+                owner: legacy_staker.clone(),
+                amount: 3_0000000,
+                cools_at: 0,
+            },
+        );
+    });
+    assert_eq!(client.voting_power(&legacy_staker), 0);
+    client.bump_stake(&legacy_staker);
+    assert_eq!(client.voting_power(&legacy_staker), 3_0000000);
+}
+
+#[test]
+fn test_proposal_lifecycle() {
+    let e: Env = create_long_lived_env();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let whale: Address = create_staker(&e, &test_data, 60_0000000);
+    let minnow: Address = create_staker(&e, &test_data, 40_0000000);
+    let client = test_data.contract_client.mock_all_auths();
+
+    let nobody_error = client
+        .try_propose(&Address::generate(&e), &ProposalAction::SetDifficulty(3))
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(nobody_error, GovernanceErrors::NotEnoughVotingPower.into());
+
+    let invalid_error = client
+        .try_propose(&whale, &ProposalAction::SetStakingCut(10_000))
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(invalid_error, ContractErrors::InvalidStakingCut.into());
+
+    pass_ledgers(&e, 1);
+    let id: u64 = client.propose(&whale, &ProposalAction::SetDifficulty(3));
+    assert_eq!(id, 1);
+    assert_eq!(
+        vec![&e, e.events().all().last().unwrap()],
+        vec![
+            &e,
+            (
+                test_data.contract_client.address.clone(),
+                (symbol_short!("proposal"), symbol_short!("created")).into_val(&e),
+                (id, whale.clone()).into_val(&e),
+            ),
+        ]
+    );
+
+    // Stake added after the proposal was created doesn't vote
+    let latecomer: Address = create_staker(&e, &test_data, 500_0000000);
+    let latecomer_error = client
+        .try_vote(&latecomer, &id, &false)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(
+        latecomer_error,
+        GovernanceErrors::NotEnoughVotingPower.into()
+    );
+
+    client.vote(&whale, &id, &true);
+    client.vote(&minnow, &id, &false);
+    let proposal = client.proposal(&id).unwrap();
+    assert_eq!(proposal.votes_for, 60_0000000);
+    assert_eq!(proposal.votes_against, 40_0000000);
+    assert_eq!(proposal.total_power, 100_0000000);
+
+    let already_voted_error = client.try_vote(&whale, &id, &true).unwrap_err().unwrap();
+    assert_eq!(already_voted_error, GovernanceErrors::AlreadyVoted.into());
+
+    let voting_error = client.try_execute_proposal(&id).unwrap_err().unwrap();
+    assert_eq!(voting_error, GovernanceErrors::ProposalIsTimeLocked.into());
+
+    pass_ledgers(&e, VOTING_PERIOD + 1);
+    let closed_error = client
+        .try_vote(&Address::generate(&e), &id, &true)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(closed_error, GovernanceErrors::VotingIsClosed.into());
+
+    let delay_error = client.try_execute_proposal(&id).unwrap_err().unwrap();
+    assert_eq!(delay_error, GovernanceErrors::ProposalIsTimeLocked.into());
+
+    // Anyone can execute it once the delay is over
+    pass_ledgers(&e, PROPOSAL_EXECUTION_DELAY);
+    e.as_contract(&test_data.contract_client.address, || {
+        e.storage().instance().extend_ttl(17280 * 60, 17280 * 60);
+    });
+    test_data.contract_client.execute_proposal(&id);
+    e.as_contract(&test_data.contract_client.address, || {
+        assert_eq!(get_state(&e).unwrap().difficulty, 3);
+    });
+    assert!(client.proposal(&id).unwrap().executed);
+
+    let executed_error = client.try_execute_proposal(&id).unwrap_err().unwrap();
+    assert_eq!(
+        executed_error,
+        GovernanceErrors::ProposalAlreadyExecuted.into()
+    );
+}
+
+#[test]
+fn test_proposals_need_quorum_and_majority() {
+    let e: Env = create_long_lived_env();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let whale: Address = create_staker(&e, &test_data, 90_0000000);
+    let minnow: Address = create_staker(&e, &test_data, 10_0000000);
+    let shrimp: Address = create_staker(&e, &test_data, 1_0000000);
+    let client = test_data.contract_client.mock_all_auths();
+
+    pass_ledgers(&e, 1);
+    let ignored: u64 = client.propose(&minnow, &ProposalAction::SetStakingCut(1_000));
+    client.vote(&minnow, &ignored, &true);
+
+    // Each proposer has a single proposal in its voting period
+    let cooling_down_error = client
+        .try_propose(&minnow, &ProposalAction::SetStakingCut(2_000))
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(
+        cooling_down_error,
+        GovernanceErrors::ProposerIsCoolingDown.into()
+    );
+
+    let rejected: u64 = client.propose(&shrimp, &ProposalAction::SetStakingCut(2_000));
+    client.vote(&minnow, &rejected, &true);
+    client.vote(&whale, &rejected, &false);
+
+    pass_ledgers(&e, VOTING_PERIOD + PROPOSAL_EXECUTION_DELAY);
+    e.as_contract(&test_data.contract_client.address, || {
+        e.storage().instance().extend_ttl(17280 * 60, 17280 * 60);
+    });
+
+    // 10% of the voting power is under the 20% quorum
+    let no_quorum_error = client.try_execute_proposal(&ignored).unwrap_err().unwrap();
+    assert_eq!(no_quorum_error, GovernanceErrors::ProposalDefeated.into());
+
+    let rejected_error = client.try_execute_proposal(&rejected).unwrap_err().unwrap();
+    assert_eq!(rejected_error, GovernanceErrors::ProposalDefeated.into());
+    assert_eq!(client.staking_cut(), 0);
+
+    client.propose(&minnow, &ProposalAction::SetStakingCut(2_000));
+}
+
+#[test]
+fn test_stakers_can_nuke_the_mine() {
+    let e: Env = create_long_lived_env();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let staker: Address = create_staker(&e, &test_data, 10_0000000);
+    let client = test_data.contract_client.mock_all_auths();
+
+    pass_ledgers(&e, 1);
+    let id: u64 = client.propose(&staker, &ProposalAction::Nuke);
+    client.vote(&staker, &id, &true);
+
+    pass_ledgers(&e, VOTING_PERIOD + PROPOSAL_EXECUTION_DELAY);
+    e.as_contract(&test_data.contract_client.address, || {
+        e.storage().instance().extend_ttl(17280 * 60, 17280 * 60);
+    });
+    client.execute_proposal(&id);
+
+    // The proposal arms the nuke, it detonates after the same grace period as a guardian nuke
+    assert!(!client.is_terminal());
+    let countdown_error = client.try_detonate_nuke().unwrap_err().unwrap();
+    assert_eq!(countdown_error, GuardianErrors::NukeIsCountingDown.into());
+
+    e.ledger()
+        .set_timestamp(e.ledger().timestamp() + NUKE_GRACE_PERIOD);
+    client.detonate_nuke();
+    assert!(client.is_terminal());
+
+    let nuked_error = client
+        .try_propose(&staker, &ProposalAction::Nuke)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(nuked_error, ContractErrors::TheMineWasNuked.into());
+}
+
+#[test]
+fn test_expired_locks_dont_vote() {
+    let e: Env = create_long_lived_env();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let staker: Address = create_staker(&e, &test_data, 10_0000000);
+    let locker: Address = Address::generate(&e);
+    let client = test_data.contract_client.mock_all_auths();
+    token::StellarAssetClient::new(&e, &test_data.fcm_client.address)
+        .mock_all_auths()
+        .mint(&locker, &1_0000000);
+    client.ve_lock(&locker, &1_0000000, &VE_MIN_LOCK);
+    assert!(client.voting_power(&locker) > 0);
+
+    // Nobody touches the lock after it expires
+    e.ledger().set_timestamp(VE_MIN_LOCK + 1);
+    assert_eq!(client.effective_stake(&locker), 0);
+    assert_eq!(client.voting_power(&locker), 0);
+
+    pass_ledgers(&e, 1);
+    let id: u64 = client.propose(&staker, &ProposalAction::SetDifficulty(3));
+    assert_eq!(client.proposal(&id).unwrap().total_power, 10_0000000);
+
+    let expired_error = client.try_vote(&locker, &id, &true).unwrap_err().unwrap();
+    assert_eq!(expired_error, GovernanceErrors::NotEnoughVotingPower.into());
+}
@@ -0,0 +1,208 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::{Address as _, Events, Ledger};
+use soroban_sdk::{symbol_short, vec, Address, Env, IntoVal, Vec};
+
+use crate::contract::NUKE_GRACE_PERIOD;
+use crate::errors::GuardianErrors;
+use crate::storage::{ArmedNuke, GuardianSet};
+use crate::tests::test_utils::{create_test_data, mine_block, start_contract, TestData};
+
+#[test]
+fn test_set_guardians() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let client = test_data.contract_client.mock_all_auths();
+    let guardian: Address = Address::generate(&e);
+    let other_guardian: Address = Address::generate(&e);
+
+    let mut too_many: Vec<Address> = Vec::new(&e);
+    for _ in 0..11 {
+        too_many.push_back(Address::generate(&e));
+    }
+
+    for (members, threshold) in [
+        (vec![&e, guardian.clone()], 0),
+        (vec![&e, guardian.clone()], 2),
+        (vec![&e, guardian.clone(), guardian.clone()], 1),
+        (too_many, 1),
+    ] {
+        let error = client
+            .try_set_guardians(&vec![&e], &members, &threshold)
+            .unwrap_err()
+            .unwrap();
+        assert_eq!(error, GuardianErrors::InvalidGuardians.into());
+    }
+
+    let members = vec![&e, guardian.clone(), other_guardian.clone()];
+    client.set_guardians(&vec![&e], &members, &2);
+    assert_eq!(
+        vec![&e, e.events().all().last().unwrap()],
+        vec![
+            &e,
+            (
+                test_data.contract_client.address.clone(),
+                (symbol_short!("guardian"), symbol_short!("set")).into_val(&e),
+                (members.clone(), 2u32).into_val(&e),
+            ),
+        ]
+    );
+    assert_eq!(
+        client.guardians(),
+        Some(GuardianSet {
+            members: members.clone(),
+            threshold: 2,
+        })
+    );
+
+    // Changing the set needs the threshold of the current guardians
+    let new_members = vec![&e, Address::generate(&e)];
+    let not_enough_error = client
+        .try_set_guardians(&vec![&e, guardian.clone()], &new_members, &1)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(not_enough_error, GuardianErrors::NotEnoughGuardians.into());
+
+    // And it can't change while a nuke is armed
+    client.fkin_nuke_it(&members);
+    let armed_error = client
+        .try_set_guardians(&members, &new_members, &1)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(armed_error, GuardianErrors::NukeIsArmed.into());
+
+    client.cancel_nuke(&members);
+    client.set_guardians(&members, &new_members, &1);
+    assert_eq!(client.guardians().unwrap().members, new_members);
+}
+
+#[test]
+fn test_the_nuke_needs_the_threshold_and_a_countdown() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let client = test_data.contract_client.mock_all_auths();
+    let first: Address = Address::generate(&e);
+    let second: Address = Address::generate(&e);
+    let third: Address = Address::generate(&e);
+
+    let no_guardians_error = client
+        .try_fkin_nuke_it(&vec![&e, test_data.genesis_block_miner.clone()])
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(no_guardians_error, GuardianErrors::NoGuardians.into());
+
+    client.set_guardians(
+        &vec![&e],
+        &vec![&e, first.clone(), second.clone(), third.clone()],
+        &2,
+    );
+
+    for (signers, expected) in [
+        (vec![&e, first.clone()], GuardianErrors::NotEnoughGuardians),
+        (
+            vec![&e, first.clone(), first.clone()],
+            GuardianErrors::NotEnoughGuardians,
+        ),
+        (
+            vec![&e, first.clone(), Address::generate(&e)],
+            GuardianErrors::NotAGuardian,
+        ),
+    ] {
+        let error = client.try_fkin_nuke_it(&signers).unwrap_err().unwrap();
+        assert_eq!(error, expected.into());
+    }
+
+    e.ledger().set_timestamp(1000);
+    client.fkin_nuke_it(&vec![&e, first.clone(), third.clone()]);
+    assert_eq!(e.auths().len(), 2);
+    assert_eq!(
+        vec![&e, e.events().all().last().unwrap()],
+        vec![
+            &e,
+            (
+                test_data.contract_client.address.clone(),
+                (symbol_short!("nuke"), symbol_short!("armed")).into_val(&e),
+                (1000 + NUKE_GRACE_PERIOD).into_val(&e),
+            ),
+        ]
+    );
+    assert_eq!(
+        client.armed_nuke(),
+        Some(ArmedNuke {
+            armed_at: 1000,
+            detonates_at: 1000 + NUKE_GRACE_PERIOD,
+        })
+    );
+
+    let armed_error = client
+        .try_fkin_nuke_it(&vec![&e, first.clone(), second.clone()])
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(armed_error, GuardianErrors::NukeAlreadyArmed.into());
+
+    // Miners keep mining during the countdown
+    mine_block(&e, &test_data, &Address::generate(&e));
+    assert!(!client.is_terminal());
+
+    e.ledger().set_timestamp(1000 + NUKE_GRACE_PERIOD - 1);
+    let countdown_error = client.try_detonate_nuke().unwrap_err().unwrap();
+    assert_eq!(countdown_error, GuardianErrors::NukeIsCountingDown.into());
+
+    e.ledger().set_timestamp(1000 + NUKE_GRACE_PERIOD);
+    test_data.contract_client.detonate_nuke();
+    assert!(client.is_terminal());
+    assert!(client.armed_nuke().is_none());
+}
+
+#[test]
+fn test_cancel_the_nuke() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let client = test_data.contract_client.mock_all_auths();
+    let first: Address = Address::generate(&e);
+    let second: Address = Address::generate(&e);
+    let guardians = vec![&e, first.clone(), second.clone()];
+    client.set_guardians(&vec![&e], &guardians, &2);
+
+    let not_armed_error = client.try_cancel_nuke(&guardians).unwrap_err().unwrap();
+    assert_eq!(not_armed_error, GuardianErrors::NukeNotArmed.into());
+
+    client.fkin_nuke_it(&guardians);
+
+    let not_enough_error = client
+        .try_cancel_nuke(&vec![&e, second.clone()])
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(not_enough_error, GuardianErrors::NotEnoughGuardians.into());
+
+    client.cancel_nuke(&guardians);
+    assert_eq!(
+        vec![&e, e.events().all().last().unwrap()],
+        vec![
+            &e,
+            (
+                test_data.contract_client.address.clone(),
+                (symbol_short!("nuke"), symbol_short!("canceled")).into_val(&e),
+                NUKE_GRACE_PERIOD.into_val(&e),
+            ),
+        ]
+    );
+    assert!(client.armed_nuke().is_none());
+
+    e.ledger().set_timestamp(NUKE_GRACE_PERIOD);
+    let canceled_error = client.try_detonate_nuke().unwrap_err().unwrap();
+    assert_eq!(canceled_error, GuardianErrors::NukeNotArmed.into());
+    assert!(!client.is_terminal());
+
+    // Once the grace period is over the nuke can't be stopped
+    client.fkin_nuke_it(&guardians);
+    e.ledger().set_timestamp(NUKE_GRACE_PERIOD * 2);
+    let too_late_error = client.try_cancel_nuke(&guardians).unwrap_err().unwrap();
+    assert_eq!(too_late_error, GuardianErrors::GracePeriodIsOver.into());
+}
@@ -0,0 +1,141 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::{Address as _, Ledger};
+use soroban_sdk::{Address, Env};
+
+use crate::contract::MAX_COOLING_PERIOD;
+use crate::errors::ContractErrors;
+use crate::storage::HotRewards;
+use crate::tests::test_utils::{create_test_data, mine_block, start_contract, TestData};
+
+#[test]
+fn test_hot_rewards_cool_down_before_withdrawing() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .set_cooling_period(&1000);
+
+    let genesis_miner: Address = test_data.genesis_block_miner.clone();
+    let miner: Address = Address::generate(&e);
+
+    // The genesis miner wins the first block, so it gets rewarded twice
+    mine_block(&e, &test_data, &genesis_miner);
+    assert_eq!(test_data.fcm_client.balance(&genesis_miner), 0);
+    assert_eq!(
+        test_data
+            .fcm_client
+            .balance(&test_data.contract_client.address),
+        1_0000000
+    );
+
+    let rewards: HotRewards = test_data
+        .contract_client
+        .hot_rewards(&genesis_miner)
+        .unwrap();
+    assert_eq!(rewards.locked, 1_0000000);
+    assert_eq!(rewards.cooled, 0);
+    assert_eq!(rewards.cools_at, 1061);
+
+    let still_hot_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_withdraw_cooled(&genesis_miner)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(still_hot_error, ContractErrors::NothingToWithdraw.into());
+
+    // After 61 seconds part of the first reward cooled and the new one is merged with the rest
+    mine_block(&e, &test_data, &miner);
+    let merged_rewards: HotRewards = test_data
+        .contract_client
+        .hot_rewards(&genesis_miner)
+        .unwrap();
+    assert_eq!(merged_rewards.cooled, 610000);
+    assert_eq!(merged_rewards.locked, 1_9390000);
+    assert_eq!(merged_rewards.cools_at, 1092);
+
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .withdraw_cooled(&genesis_miner);
+    assert_eq!(test_data.fcm_client.balance(&genesis_miner), 610000);
+
+    e.ledger().set_timestamp(1092);
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .withdraw_cooled(&genesis_miner);
+    assert_eq!(test_data.fcm_client.balance(&genesis_miner), 2_0000000);
+    assert!(test_data
+        .contract_client
+        .hot_rewards(&genesis_miner)
+        .is_none());
+}
+
+#[test]
+fn test_cooling_period_limits() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let invalid_period_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_set_cooling_period(&(3600 * 24 * 61))
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(
+        invalid_period_error,
+        ContractErrors::InvalidCoolingPeriod.into()
+    );
+
+    // Without a cooling period rewards go straight to the winner
+    mine_block(&e, &test_data, &Address::generate(&e));
+    assert_eq!(
+        test_data.fcm_client.balance(&test_data.genesis_block_miner),
+        1_0000000
+    );
+}
+
+#[test]
+fn test_hot_rewards_survive_archival() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .set_cooling_period(&MAX_COOLING_PERIOD);
+
+    let genesis_miner: Address = test_data.genesis_block_miner.clone();
+    mine_block(&e, &test_data, &Address::generate(&e));
+
+    let pass_days = |days: u32| {
+        // The reactor itself is kept alive by the activity of every miner
+        e.as_contract(&test_data.contract_client.address, || {
+            e.storage().instance().extend_ttl(17280 * 60, 17280 * 60);
+        });
+
+        e.ledger().with_mut(|ledger| {
+            ledger.sequence_number += 17280 * days;
+            ledger.timestamp += 3600 * 24 * days as u64;
+        });
+    };
+
+    // The miner stops winning blocks, bumping its stake keeps the vesting rewards alive too
+    pass_days(40);
+    test_data.contract_client.bump_stake(&genesis_miner);
+    pass_days(40);
+
+    let rewards: HotRewards = test_data
+        .contract_client
+        .hot_rewards(&genesis_miner)
+        .unwrap();
+    assert_eq!(rewards.cooled, 1_0000000);
+    assert_eq!(rewards.locked, 0);
+}
@@ -0,0 +1,205 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::{Address as _, Ledger};
+use soroban_sdk::{token, Address, BytesN, Env};
+use st_fcm::contract::{StakedFcmContract, StakedFcmContractClient};
+
+use crate::contract::STAKE_COOLDOWN;
+use crate::errors::ContractErrors;
+use crate::storage::{get_block, get_state, set_block, set_state, Block, ReactorState};
+use crate::tests::test_utils::{create_test_data, next_block_hash, start_contract, TestData};
+
+fn create_receipt_token<'a>(e: &Env, admin: &Address) -> StakedFcmContractClient<'a> {
+    let deployer: Address = Address::generate(e);
+    let salt: BytesN<32> = BytesN::from_array(e, &[1u8; 32]);
+    let deployed_address: Address = e
+        .deployer()
+        .with_address(deployer.clone(), salt.clone())
+        .deployed_address();
+    let receipt_client = StakedFcmContractClient::new(
+        e,
+        &e.register_contract(&deployed_address, StakedFcmContract),
+    );
+    receipt_client
+        .mock_all_auths()
+        .initialize(&deployer, &salt, admin);
+    receipt_client
+}
+
+// Moves the reactor to the given block, the requirement at block 80,000 is 8 FCM
+fn jump_to_block(e: &Env, test_data: &TestData, index: u64) {
+    e.as_contract(&test_data.contract_client.address, || {
+        let mut state: ReactorState = get_state(e).unwrap();
+        let mut block: Block = get_block(e, &state.current).unwrap();
+        state.current = index;
+        block.index = index;
+        set_state(e, &state);
+        set_block(e, &block);
+    });
+}
+
+#[test]
+fn test_set_receipt_token() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    // The reactor must be able to mint and burn the receipts
+    let foreign_receipt = create_receipt_token(&e, &Address::generate(&e));
+    let invalid_receipt_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_set_receipt_token(&foreign_receipt.address)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(
+        invalid_receipt_error,
+        ContractErrors::InvalidReceiptToken.into()
+    );
+
+    let receipt = create_receipt_token(&e, &test_data.contract_client.address);
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .set_receipt_token(&receipt.address);
+    assert_eq!(
+        test_data.contract_client.receipt_token(),
+        Some(receipt.address.clone())
+    );
+
+    let already_set_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_set_receipt_token(&receipt.address)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(
+        already_set_error,
+        ContractErrors::ReceiptTokenAlreadySet.into()
+    );
+}
+
+#[test]
+fn test_receipts_carry_the_stake() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let receipt = create_receipt_token(&e, &test_data.contract_client.address);
+    let receipt_client = token::Client::new(&e, &receipt.address);
+    let client = test_data.contract_client.mock_all_auths();
+    client.set_receipt_token(&receipt.address);
+
+    let alice: Address = Address::generate(&e);
+    let bob: Address = Address::generate(&e);
+    token::StellarAssetClient::new(&e, &test_data.fcm_client.address)
+        .mock_all_auths()
+        .mint(&alice, &8_0000000);
+
+    // The receipts are kept in the reactor for the staker
+    client.stake(&alice, &8_0000000);
+    assert_eq!(client.receipted_stake(&alice), 8_0000000);
+    assert_eq!(client.receipt_deposit(&alice), 8_0000000);
+    assert_eq!(receipt_client.balance(&alice), 0);
+    assert_eq!(client.effective_stake(&alice), 8_0000000);
+    assert_eq!(client.sweepable(&receipt.address), 0);
+
+    // Receipts only count while they are deposited in the reactor
+    client.withdraw_receipt(&alice, &8_0000000);
+    assert_eq!(receipt_client.balance(&alice), 8_0000000);
+    assert_eq!(client.effective_stake(&alice), 0);
+
+    receipt_client
+        .mock_all_auths()
+        .transfer(&alice, &bob, &8_0000000);
+    assert_eq!(client.effective_stake(&bob), 0);
+    client.deposit_receipt(&bob, &8_0000000);
+    assert_eq!(client.effective_stake(&alice), 0);
+    assert_eq!(client.effective_stake(&bob), 8_0000000);
+
+    jump_to_block(&e, &test_data, 80_000);
+
+    let (hash, message, nonce) = next_block_hash(&e, &test_data, &alice);
+    let not_enough_staked_error = client
+        .try_mine(&hash, &message, &nonce, &alice)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(
+        not_enough_staked_error,
+        ContractErrors::NotEnoughStaked.into()
+    );
+    let (hash, message, nonce) = next_block_hash(&e, &test_data, &bob);
+    client.mine(&hash, &message, &nonce, &bob);
+
+    // The receipts can't leave while they keep bob in the current attempt
+    let in_attempt_error = client.try_withdraw_receipt(&bob, &1).unwrap_err().unwrap();
+    assert_eq!(
+        in_attempt_error,
+        ContractErrors::StakeBelowRequirement.into()
+    );
+
+    // Without the receipts the stake can't be taken back
+    e.ledger().set_timestamp(STAKE_COOLDOWN + 1);
+    let not_enough_receipts_error = client
+        .try_un_stake(&alice, &8_0000000)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(
+        not_enough_receipts_error,
+        ContractErrors::NotEnoughReceipts.into()
+    );
+
+    // Once the block is closed they can move again
+    jump_to_block(&e, &test_data, 80_001);
+    client.withdraw_receipt(&bob, &8_0000000);
+    receipt_client
+        .mock_all_auths()
+        .transfer(&bob, &alice, &8_0000000);
+    client.un_stake(&alice, &8_0000000);
+    assert_eq!(receipt_client.balance(&alice), 0);
+    assert_eq!(client.receipted_stake(&alice), 0);
+    assert_eq!(test_data.fcm_client.balance(&alice), 8_0000000);
+}
+
+#[test]
+fn test_unstake_burns_the_deposited_receipts() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let receipt = create_receipt_token(&e, &test_data.contract_client.address);
+    let receipt_client = token::Client::new(&e, &receipt.address);
+    let client = test_data.contract_client.mock_all_auths();
+    client.set_receipt_token(&receipt.address);
+
+    let staker: Address = Address::generate(&e);
+    token::StellarAssetClient::new(&e, &test_data.fcm_client.address)
+        .mock_all_auths()
+        .mint(&staker, &10_0000000);
+    client.stake(&staker, &10_0000000);
+
+    // Part of the receipts are in the wallet, the rest is still deposited
+    client.withdraw_receipt(&staker, &4_0000000);
+
+    let zero_error = client
+        .try_deposit_receipt(&staker, &0)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(zero_error, ContractErrors::InvalidAmount.into());
+    let too_much_error = client
+        .try_withdraw_receipt(&staker, &6_0000001)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(too_much_error, ContractErrors::NotEnoughReceipts.into());
+
+    e.ledger().set_timestamp(STAKE_COOLDOWN + 1);
+    client.un_stake(&staker, &8_0000000);
+    assert_eq!(client.receipt_deposit(&staker), 0);
+    assert_eq!(receipt_client.balance(&staker), 2_0000000);
+    assert_eq!(
+        receipt_client.balance(&test_data.contract_client.address),
+        0
+    );
+    assert_eq!(client.receipted_stake(&staker), 2_0000000);
+    assert_eq!(test_data.fcm_client.balance(&staker), 8_0000000);
+}
@@ -0,0 +1,72 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::{Address, Env, TryFromVal, Val};
+
+use crate::errors::ContractErrors;
+use crate::storage::{
+    get_block, get_state, Block, ReactorState, Role, StorageKeys, VersionedBlock, VersionedState,
+};
+use crate::tests::test_utils::{create_test_data, mine_block, start_contract, TestData};
+
+#[test]
+fn test_migrate_from_the_first_layout() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    // Write the state and the genesis block as a version 1 reactor did
+    e.as_contract(&test_data.contract_client.address, || {
+        let state: ReactorState = get_state(&e).unwrap();
+        let block: Block = get_block(&e, &0).unwrap();
+        e.storage().instance().set(&StorageKeys::MineState, &state);
+        e.storage().persistent().set(&StorageKeys::Block(0), &block);
+        e.storage().instance().remove(&StorageKeys::SchemaVersion);
+        e.storage().instance().remove(&StorageKeys::Admin);
+        e.storage().instance().remove(&StorageKeys::HeldFcm);
+    });
+    assert_eq!(test_data.contract_client.schema_version(), 1);
+
+    // The old layout keeps working and it's rewritten once it's read
+    let miner: Address = Address::generate(&e);
+    mine_block(&e, &test_data, &miner);
+    e.as_contract(&test_data.contract_client.address, || {
+        let raw_state: Val = e.storage().instance().get(&StorageKeys::MineState).unwrap();
+        let raw_block: Val = e
+            .storage()
+            .persistent()
+            .get(&StorageKeys::Block(0))
+            .unwrap();
+        assert!(VersionedState::try_from_val(&e, &raw_state).is_ok());
+        assert!(VersionedBlock::try_from_val(&e, &raw_block).is_ok());
+        assert_eq!(get_state(&e).unwrap().current, 1);
+    });
+
+    let missing_role_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_migrate(&miner)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(missing_role_error, ContractErrors::MissingRole.into());
+
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .migrate(&test_data.genesis_block_miner);
+    assert_eq!(test_data.contract_client.schema_version(), 3);
+    assert!(test_data
+        .contract_client
+        .has_role(&Role::Upgrader, &test_data.genesis_block_miner));
+
+    let already_migrated_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_migrate(&test_data.genesis_block_miner)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(
+        already_migrated_error,
+        ContractErrors::AlreadyMigrated.into()
+    );
+}
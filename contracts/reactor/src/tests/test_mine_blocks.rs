@@ -3,10 +3,10 @@
 use crate::contract::create_block_hash;
 use crate::errors::ContractErrors;
 use crate::storage::{
-    get_attempt, get_block, get_miner_attempt, get_state, set_state, Attempt, Block, MinerAttempt,
-    ReactorState,
+    get_attempt, get_block, get_miner_attempt, get_state, set_block, set_state, Attempt, Block,
+    MinerAttempt, ReactorState,
 };
-use crate::tests::test_utils::{create_test_data, start_contract, TestData};
+use crate::tests::test_utils::{create_test_data, find_nonce, start_contract, TestData};
 use hex::encode;
 use soroban_sdk::testutils::arbitrary::std::println;
 use soroban_sdk::testutils::{Address as _, BytesN as __, Ledger, MockAuth, MockAuthInvoke};
@@ -31,11 +31,12 @@ fn test_mining_a_block() {
 
     let prev_block: Block = prev_block_option.unwrap();
 
-    let hash: BytesN<32> = create_block_hash(&e, &1, &message, &prev_block.hash, &0, &miner);
+    let nonce: u64 = find_nonce(&e, &1, &message, &prev_block.hash, &0, &miner);
+    let hash: BytesN<32> = create_block_hash(&e, &1, &message, &prev_block.hash, &nonce, &miner);
 
     assert!(test_data
         .contract_client
-        .try_mine(&hash, &message, &0, &miner)
+        .try_mine(&hash, &message, &nonce, &miner)
         .is_err());
 
     assert_eq!(
@@ -52,11 +53,11 @@ fn test_mining_a_block() {
             invoke: &MockAuthInvoke {
                 contract: &test_data.contract_client.address,
                 fn_name: "mine",
-                args: (hash.clone(), message.clone(), 0u64, miner.clone()).into_val(&e),
+                args: (hash.clone(), message.clone(), nonce, miner.clone()).into_val(&e),
                 sub_invokes: &[],
             },
         }])
-        .mine(&hash, &message, &0, &miner);
+        .mine(&hash, &message, &nonce, &miner);
 
     e.as_contract(&test_data.contract_client.address, || {
         let state: ReactorState = get_state(&e).unwrap();
@@ -77,55 +78,75 @@ fn test_mining_a_block() {
 
     let first_miner: Address = Address::generate(&e);
     e.ledger().set_timestamp(3670);
+    let nonce: u64 = find_nonce(&e, &2, &message, &new_prev_block.hash, &0, &first_miner);
     test_data.contract_client.mock_all_auths().mine(
-        &create_block_hash(&e, &2, &message, &new_prev_block.hash, &0, &first_miner),
+        &create_block_hash(&e, &2, &message, &new_prev_block.hash, &nonce, &first_miner),
         &message,
-        &0,
+        &nonce,
         &first_miner,
     );
 
     let second_miner: Address = Address::generate(&e);
     e.ledger().set_timestamp(3680);
+    let nonce: u64 = find_nonce(&e, &2, &message, &new_prev_block.hash, &0, &second_miner);
     test_data.contract_client.mock_all_auths().mine(
-        &create_block_hash(&e, &2, &message, &new_prev_block.hash, &0, &second_miner),
+        &create_block_hash(
+            &e,
+            &2,
+            &message,
+            &new_prev_block.hash,
+            &nonce,
+            &second_miner,
+        ),
         &message,
-        &0,
+        &nonce,
         &second_miner,
     );
 
     let third_miner: Address = Address::generate(&e);
     e.ledger().set_timestamp(3690);
+    let nonce: u64 = find_nonce(&e, &2, &message, &new_prev_block.hash, &0, &third_miner);
     test_data.contract_client.mock_all_auths().mine(
-        &create_block_hash(&e, &2, &message, &new_prev_block.hash, &0, &third_miner),
+        &create_block_hash(&e, &2, &message, &new_prev_block.hash, &nonce, &third_miner),
         &message,
-        &0,
+        &nonce,
         &third_miner,
     );
 
     let fourth_miner: Address = Address::generate(&e);
     e.ledger().set_timestamp(3700);
+    let nonce: u64 = find_nonce(&e, &2, &message, &new_prev_block.hash, &0, &fourth_miner);
     test_data.contract_client.mock_all_auths().mine(
-        &create_block_hash(&e, &2, &message, &new_prev_block.hash, &0, &fourth_miner),
+        &create_block_hash(
+            &e,
+            &2,
+            &message,
+            &new_prev_block.hash,
+            &nonce,
+            &fourth_miner,
+        ),
         &message,
-        &0,
+        &nonce,
         &fourth_miner,
     );
 
     let fifth_miner: Address = Address::generate(&e);
     e.ledger().set_timestamp(3710);
+    let nonce: u64 = find_nonce(&e, &2, &message, &new_prev_block.hash, &0, &fifth_miner);
     test_data.contract_client.mock_all_auths().mine(
-        &create_block_hash(&e, &2, &message, &new_prev_block.hash, &0, &fifth_miner),
+        &create_block_hash(&e, &2, &message, &new_prev_block.hash, &nonce, &fifth_miner),
         &message,
-        &0,
+        &nonce,
         &fifth_miner,
     );
 
     let sixth_miner: Address = Address::generate(&e);
     e.ledger().set_timestamp(3720);
+    let nonce: u64 = find_nonce(&e, &2, &message, &new_prev_block.hash, &0, &sixth_miner);
     test_data.contract_client.mock_all_auths().mine(
-        &create_block_hash(&e, &2, &message, &new_prev_block.hash, &0, &sixth_miner),
+        &create_block_hash(&e, &2, &message, &new_prev_block.hash, &nonce, &sixth_miner),
         &message,
-        &0,
+        &nonce,
         &sixth_miner,
     );
 
@@ -175,10 +196,11 @@ fn test_mining_a_block() {
 
     let final_miner: Address = Address::generate(&e);
     e.ledger().set_timestamp(3730);
+    let nonce: u64 = find_nonce(&e, &2, &message, &new_prev_block.hash, &0, &final_miner);
     test_data.contract_client.mock_all_auths().mine(
-        &create_block_hash(&e, &2, &message, &new_prev_block.hash, &0, &final_miner),
+        &create_block_hash(&e, &2, &message, &new_prev_block.hash, &nonce, &final_miner),
         &message,
-        &0,
+        &nonce,
         &final_miner,
     );
 
@@ -292,9 +314,20 @@ fn test_mining_with_high_difficulty() {
         "CAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAITA4",
     ));
     let message: String = String::from_str(&e, "The random message");
+
+    // The nonce below was found for this genesis hash, so the genesis block is pinned to it
     let mut prev_block_option: Option<Block> = None;
     e.as_contract(&test_data.contract_client.address, || {
-        prev_block_option = Some(get_block(&e, &0).unwrap());
+        let mut genesis: Block = get_block(&e, &0).unwrap();
+        genesis.hash = BytesN::from_array(
+            &e,
+            &[
+                70, 207, 147, 217, 66, 230, 4, 40, 240, 225, 22, 22, 65, 42, 200, 97, 41, 66, 186,
+                145, 104, 184, 92, 64, 14, 97, 241, 104, 247, 151, 78, 26,
+            ],
+        );
+        set_block(&e, &genesis);
+        prev_block_option = Some(genesis);
     });
     let prev_block: Block = prev_block_option.unwrap();
 
@@ -329,10 +362,10 @@ pub fn test_create_block_hash() {
     let message: String = String::from_str(&e, "The random message");
     let nonce: u64 = 5114425;
 
-    println!("index: {:?}", &1u64.clone().to_xdr(&e));
+    println!("index: {:?}", &1u64.to_xdr(&e));
     println!("message: {:?}", &message.clone().clone().to_xdr(&e));
     println!("prev_hash: {:?}", &prev_hash.clone().clone().to_xdr(&e));
-    println!("nonce: {:?}", &nonce.clone().to_xdr(&e));
+    println!("nonce: {:?}", &nonce.to_xdr(&e));
     println!("miner: {:?}", &miner.clone().to_xdr(&e));
 
     let hash: BytesN<32> = create_block_hash(&e, &1, &message, &prev_hash, &nonce, &miner);
@@ -0,0 +1,119 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::{Address as _, Events};
+use soroban_sdk::{symbol_short, vec, Address, Env, IntoVal};
+
+use crate::errors::ContractErrors;
+use crate::tests::test_utils::{
+    create_test_data, mine_block, next_block_hash, start_contract, TestData,
+};
+
+#[test]
+fn test_denied_miners_cant_mine() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let client = test_data.contract_client.mock_all_auths();
+    let bot: Address = Address::generate(&e);
+
+    client.set_denied(&bot, &true);
+    assert!(client.is_denied(&bot));
+    assert_eq!(
+        vec![&e, e.events().all().last().unwrap()],
+        vec![
+            &e,
+            (
+                test_data.contract_client.address.clone(),
+                (symbol_short!("denylist"), symbol_short!("added")).into_val(&e),
+                bot.into_val(&e),
+            ),
+        ]
+    );
+
+    let (hash, message, nonce) = next_block_hash(&e, &test_data, &bot);
+    let denied_error = client
+        .try_mine(&hash, &message, &nonce, &bot)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(denied_error, ContractErrors::MinerIsDenied.into());
+
+    client.set_denied(&bot, &false);
+    assert!(!client.is_denied(&bot));
+    assert_eq!(
+        vec![&e, e.events().all().last().unwrap()],
+        vec![
+            &e,
+            (
+                test_data.contract_client.address.clone(),
+                (symbol_short!("denylist"), symbol_short!("removed")).into_val(&e),
+                bot.into_val(&e),
+            ),
+        ]
+    );
+
+    client.mine(&hash, &message, &nonce, &bot);
+}
+
+#[test]
+fn test_allowlisted_test_phase() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let client = test_data.contract_client.mock_all_auths();
+    let tester: Address = Address::generate(&e);
+    let outsider: Address = Address::generate(&e);
+
+    client.set_allowed(&tester, &true);
+    assert!(client.is_allowed(&tester));
+    assert_eq!(
+        vec![&e, e.events().all().last().unwrap()],
+        vec![
+            &e,
+            (
+                test_data.contract_client.address.clone(),
+                (symbol_short!("allowlist"), symbol_short!("added")).into_val(&e),
+                tester.into_val(&e),
+            ),
+        ]
+    );
+
+    client.set_allowlist_enabled(&true);
+    assert!(client.allowlist_enabled());
+    assert_eq!(
+        vec![&e, e.events().all().last().unwrap()],
+        vec![
+            &e,
+            (
+                test_data.contract_client.address.clone(),
+                (symbol_short!("allowlist"), symbol_short!("enabled")).into_val(&e),
+                true.into_val(&e),
+            ),
+        ]
+    );
+
+    let (hash, message, nonce) = next_block_hash(&e, &test_data, &outsider);
+    let not_allowed_error = client
+        .try_mine(&hash, &message, &nonce, &outsider)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(not_allowed_error, ContractErrors::MinerIsNotAllowed.into());
+
+    mine_block(&e, &test_data, &tester);
+
+    // The denylist still applies to allowed miners
+    client.set_denied(&tester, &true);
+    let (hash, message, nonce) = next_block_hash(&e, &test_data, &tester);
+    let denied_error = client
+        .try_mine(&hash, &message, &nonce, &tester)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(denied_error, ContractErrors::MinerIsDenied.into());
+
+    // Once the test phase is over everyone can mine again
+    client.set_allowlist_enabled(&false);
+    client.set_allowed(&tester, &false);
+    assert!(!client.is_allowed(&tester));
+    mine_block(&e, &test_data, &outsider);
+}
@@ -0,0 +1,143 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::{Address as _, Ledger};
+use soroban_sdk::{token, vec, Address, Env};
+
+use crate::contract::STAKE_COOLDOWN;
+use crate::errors::ContractErrors;
+use crate::storage::{set_deferred_rewards, StakeAsset, UnstakeQuote};
+use crate::tests::test_utils::{
+    create_test_data, create_token, next_block_hash, nuke_mine, start_contract, TestData,
+};
+
+#[test]
+fn test_the_nuke_rejects_new_stake() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let miner: Address = Address::generate(&e);
+    let (lp_share, lp_share_admin) = create_token(&e);
+    token::StellarAssetClient::new(&e, &test_data.fcm_client.address)
+        .mock_all_auths()
+        .mint(&miner, &10_0000000);
+    lp_share_admin.mock_all_auths().mint(&miner, &10_0000000);
+
+    let client = test_data.contract_client.mock_all_auths();
+    client.set_stake_assets(&vec![
+        &e,
+        StakeAsset {
+            token: lp_share.address.clone(),
+            weight: 1_0000000,
+        },
+    ]);
+    client.ve_lock(&miner, &5_0000000, &STAKE_COOLDOWN);
+
+    assert!(!client.is_terminal());
+    nuke_mine(&e, &test_data);
+    assert!(client.is_terminal());
+
+    let nuked = ContractErrors::TheMineWasNuked.into();
+    assert_eq!(
+        client.try_stake(&miner, &1_0000000).unwrap_err().unwrap(),
+        nuked
+    );
+    assert_eq!(
+        client
+            .try_stake_asset(&miner, &lp_share.address, &1_0000000)
+            .unwrap_err()
+            .unwrap(),
+        nuked
+    );
+    assert_eq!(
+        client
+            .try_ve_lock(&Address::generate(&e), &1_0000000, &STAKE_COOLDOWN)
+            .unwrap_err()
+            .unwrap(),
+        nuked
+    );
+    assert_eq!(
+        client
+            .try_ve_increase(&miner, &1_0000000)
+            .unwrap_err()
+            .unwrap(),
+        nuked
+    );
+
+    // No new FCM is minted for deferred rewards either
+    e.as_contract(&test_data.contract_client.address, || {
+        set_deferred_rewards(&e, &miner, &1_0000000);
+    });
+    assert_eq!(
+        client.try_claim_deferred(&miner).unwrap_err().unwrap(),
+        nuked
+    );
+}
+
+#[test]
+fn test_stakers_mid_cooldown_leave_at_once() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let miner: Address = Address::generate(&e);
+    let staker: Address = Address::generate(&e);
+    let (lp_share, lp_share_admin) = create_token(&e);
+    let fcm_admin = token::StellarAssetClient::new(&e, &test_data.fcm_client.address);
+    fcm_admin.mock_all_auths().mint(&miner, &30_0000000);
+    fcm_admin.mock_all_auths().mint(&staker, &10_0000000);
+    lp_share_admin.mock_all_auths().mint(&miner, &10_0000000);
+
+    let client = test_data.contract_client.mock_all_auths();
+    client.set_stake_assets(&vec![
+        &e,
+        StakeAsset {
+            token: lp_share.address.clone(),
+            weight: 1_0000000,
+        },
+    ]);
+    client.stake(&miner, &10_0000000);
+    client.stake_asset(&miner, &lp_share.address, &10_0000000);
+    client.ve_lock(&miner, &20_0000000, &(STAKE_COOLDOWN * 4));
+    client.stake(&staker, &10_0000000);
+
+    // The miner joins the current attempt and every position is still hot
+    let (hash, message, nonce) = next_block_hash(&e, &test_data, &miner);
+    client.mine(&hash, &message, &nonce, &miner);
+    e.ledger().set_timestamp(STAKE_COOLDOWN / 2);
+    assert_eq!(
+        client
+            .try_un_stake(&miner, &10_0000000)
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::StakeIsStillHot.into()
+    );
+
+    nuke_mine(&e, &test_data);
+
+    // No penalty is taken from a dead mine
+    assert_eq!(
+        client.emergency_unstake_quote(&staker),
+        UnstakeQuote {
+            amount: 10_0000000,
+            penalty: 0,
+        }
+    );
+    assert_eq!(
+        client.emergency_unstake(&staker),
+        UnstakeQuote {
+            amount: 10_0000000,
+            penalty: 0,
+        }
+    );
+    assert_eq!(test_data.fcm_client.balance(&staker), 10_0000000);
+
+    // Neither the cooldowns, the locks nor the attempt keep the miner's stake in the mine
+    client.un_stake(&miner, &10_0000000);
+    client.un_stake_asset(&miner, &lp_share.address, &10_0000000);
+    client.ve_withdraw(&miner);
+    assert_eq!(test_data.fcm_client.balance(&miner), 30_0000000);
+    assert_eq!(lp_share.balance(&miner), 10_0000000);
+    assert!(client.stake_lots(&miner).is_empty());
+    assert!(client.ve_lock_of(&miner).is_none());
+}
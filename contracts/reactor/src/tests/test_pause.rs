@@ -0,0 +1,221 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::{Address as _, Events, Ledger};
+use soroban_sdk::{symbol_short, token, vec, Address, Env, IntoVal};
+
+use crate::contract::STAKE_COOLDOWN;
+use crate::errors::ContractErrors;
+use crate::storage::{get_state, set_deferred_rewards, StakeAsset};
+use crate::tests::test_utils::{
+    create_test_data, create_token, mine_block, next_block_hash, nuke_mine, start_contract,
+    TestData,
+};
+
+#[test]
+fn test_pause_and_unpause() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let pauser: Address = test_data.genesis_block_miner.clone();
+    let random: Address = Address::generate(&e);
+
+    let missing_role_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_pause(&random)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(missing_role_error, ContractErrors::MissingRole.into());
+
+    let not_paused_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_unpause(&pauser)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(not_paused_error, ContractErrors::NotPaused.into());
+
+    test_data.contract_client.mock_all_auths().pause(&pauser);
+    assert!(test_data.contract_client.is_paused());
+    assert_eq!(
+        vec![&e, e.events().all().last().unwrap()],
+        vec![
+            &e,
+            (
+                test_data.contract_client.address.clone(),
+                (symbol_short!("pause"), symbol_short!("on")).into_val(&e),
+                pauser.into_val(&e),
+            ),
+        ]
+    );
+
+    let already_paused_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_pause(&pauser)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(already_paused_error, ContractErrors::AlreadyPaused.into());
+
+    test_data.contract_client.mock_all_auths().unpause(&pauser);
+    assert!(!test_data.contract_client.is_paused());
+    assert_eq!(
+        vec![&e, e.events().all().last().unwrap()],
+        vec![
+            &e,
+            (
+                test_data.contract_client.address.clone(),
+                (symbol_short!("pause"), symbol_short!("off")).into_val(&e),
+                pauser.into_val(&e),
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_paused_entry_points() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let pauser: Address = test_data.genesis_block_miner.clone();
+    let miner: Address = Address::generate(&e);
+    let (lp_share, lp_share_admin) = create_token(&e);
+    token::StellarAssetClient::new(&e, &test_data.fcm_client.address)
+        .mock_all_auths()
+        .mint(&miner, &40_0000000);
+    lp_share_admin.mock_all_auths().mint(&miner, &10_0000000);
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .set_stake_assets(&vec![
+            &e,
+            StakeAsset {
+                token: lp_share.address.clone(),
+                weight: 1_0000000,
+            },
+        ]);
+
+    // Positions opened before the incident
+    let client = test_data.contract_client.mock_all_auths();
+    client.stake(&miner, &10_0000000);
+    client.stake_asset(&miner, &lp_share.address, &5_0000000);
+    client.ve_lock(&miner, &10_0000000, &STAKE_COOLDOWN);
+
+    client.pause(&pauser);
+
+    // Nothing new can get into the mine
+    let (hash, message, nonce) = next_block_hash(&e, &test_data, &miner);
+    let paused = ContractErrors::TheMineIsPaused.into();
+    assert_eq!(
+        client
+            .try_mine(&hash, &message, &nonce, &miner)
+            .unwrap_err()
+            .unwrap(),
+        paused
+    );
+    assert_eq!(
+        client.try_stake(&miner, &1_0000000).unwrap_err().unwrap(),
+        paused
+    );
+    assert_eq!(
+        client
+            .try_stake_asset(&miner, &lp_share.address, &1_0000000)
+            .unwrap_err()
+            .unwrap(),
+        paused
+    );
+    assert_eq!(
+        client
+            .try_ve_increase(&miner, &1_0000000)
+            .unwrap_err()
+            .unwrap(),
+        paused
+    );
+    assert_eq!(
+        client
+            .try_ve_lock(&Address::generate(&e), &1_0000000, &STAKE_COOLDOWN)
+            .unwrap_err()
+            .unwrap(),
+        paused
+    );
+    assert_eq!(
+        client
+            .try_burn_for_boost(&miner, &1_0000000)
+            .unwrap_err()
+            .unwrap(),
+        paused
+    );
+
+    // Deferred rewards would mint new FCM
+    e.as_contract(&test_data.contract_client.address, || {
+        set_deferred_rewards(&e, &miner, &1_0000000);
+    });
+    assert_eq!(
+        client.try_claim_deferred(&miner).unwrap_err().unwrap(),
+        paused
+    );
+
+    // But everything can get out of it
+    e.ledger().set_timestamp(STAKE_COOLDOWN + 1);
+    client.un_stake(&miner, &4_0000000);
+    client.emergency_unstake(&miner);
+    client.un_stake_asset(&miner, &lp_share.address, &5_0000000);
+    client.ve_withdraw(&miner);
+    assert_eq!(test_data.fcm_client.balance(&miner), 40_0000000);
+    assert_eq!(lp_share.balance(&miner), 10_0000000);
+
+    // Once the incident is over the mine works as before
+    client.unpause(&pauser);
+    client.stake(&miner, &10_0000000);
+    mine_block(&e, &test_data, &miner);
+    e.as_contract(&test_data.contract_client.address, || {
+        assert_eq!(get_state(&e).unwrap().current, 1);
+    });
+}
+
+#[test]
+fn test_the_nuke_is_terminal() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let pauser: Address = test_data.genesis_block_miner.clone();
+    let miner: Address = Address::generate(&e);
+    token::StellarAssetClient::new(&e, &test_data.fcm_client.address)
+        .mock_all_auths()
+        .mint(&miner, &10_0000000);
+
+    let client = test_data.contract_client.mock_all_auths();
+    client.stake(&miner, &10_0000000);
+    client.pause(&pauser);
+
+    // Nuking a paused mine leaves it nuked, there is nothing to unpause anymore
+    nuke_mine(&e, &test_data);
+    assert!(!client.is_paused());
+
+    let nuked = ContractErrors::TheMineWasNuked.into();
+    assert_eq!(client.try_pause(&pauser).unwrap_err().unwrap(), nuked);
+    assert_eq!(client.try_unpause(&pauser).unwrap_err().unwrap(), nuked);
+    assert_eq!(
+        client
+            .try_fkin_nuke_it(&vec![&e, pauser.clone()])
+            .unwrap_err()
+            .unwrap(),
+        nuked
+    );
+
+    let (hash, message, nonce) = next_block_hash(&e, &test_data, &miner);
+    assert_eq!(
+        client
+            .try_mine(&hash, &message, &nonce, &miner)
+            .unwrap_err()
+            .unwrap(),
+        nuked
+    );
+
+    e.ledger().set_timestamp(STAKE_COOLDOWN + 1);
+    client.un_stake(&miner, &10_0000000);
+    assert_eq!(test_data.fcm_client.balance(&miner), 10_0000000);
+}
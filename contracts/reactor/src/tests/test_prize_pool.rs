@@ -0,0 +1,145 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::{vec, Address, Env};
+
+use crate::errors::ContractErrors;
+use crate::tests::test_utils::{
+    create_test_data, create_token, mine_block, nuke_mine, start_contract, TestData,
+};
+
+#[test]
+fn test_prize_pool_drips_to_block_winners() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let (xlm_client, xlm_admin_client) = create_token(&e);
+    let donor: Address = Address::generate(&e);
+    xlm_admin_client
+        .mock_all_auths()
+        .mint(&donor, &1000_0000000);
+
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .set_prize_pool(&vec![&e, xlm_client.address.clone()], &1000);
+
+    test_data.contract_client.mock_all_auths().deposit_prize(
+        &donor,
+        &xlm_client.address,
+        &1000_0000000,
+    );
+
+    assert_eq!(xlm_client.balance(&donor), 0);
+    assert_eq!(
+        test_data.contract_client.prize_pool(&xlm_client.address),
+        1000_0000000
+    );
+    assert_eq!(
+        test_data
+            .contract_client
+            .prize_pool_drip(&xlm_client.address),
+        100_0000000
+    );
+
+    let first_miner: Address = Address::generate(&e);
+    let second_miner: Address = Address::generate(&e);
+
+    // The first block closes paying the genesis miner, the second one pays the first miner
+    mine_block(&e, &test_data, &first_miner);
+    assert_eq!(
+        xlm_client.balance(&test_data.genesis_block_miner),
+        100_0000000
+    );
+
+    mine_block(&e, &test_data, &second_miner);
+    assert_eq!(xlm_client.balance(&first_miner), 90_0000000);
+    assert_eq!(xlm_client.balance(&second_miner), 0);
+
+    assert_eq!(
+        test_data.contract_client.prize_pool(&xlm_client.address),
+        810_0000000
+    );
+    assert_eq!(
+        test_data
+            .contract_client
+            .prize_pool_drip(&xlm_client.address),
+        81_0000000
+    );
+}
+
+#[test]
+fn test_prize_pool_deposit_and_withdraw_rules() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let (xlm_client, xlm_admin_client) = create_token(&e);
+    let (other_client, _) = create_token(&e);
+    let donor: Address = Address::generate(&e);
+    xlm_admin_client.mock_all_auths().mint(&donor, &100_0000000);
+
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .set_prize_pool(&vec![&e, xlm_client.address.clone()], &500);
+
+    let invalid_config_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_set_prize_pool(&vec![&e, xlm_client.address.clone()], &10_001)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(
+        invalid_config_error,
+        ContractErrors::InvalidPrizePoolConfig.into()
+    );
+
+    let not_in_pool_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_deposit_prize(&donor, &other_client.address, &1)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(
+        not_in_pool_error,
+        ContractErrors::TokenNotInPrizePool.into()
+    );
+
+    test_data.contract_client.mock_all_auths().deposit_prize(
+        &donor,
+        &xlm_client.address,
+        &100_0000000,
+    );
+
+    let locked_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_withdraw_prize(&xlm_client.address, &donor, &100_0000000)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(locked_error, ContractErrors::PrizePoolIsLocked.into());
+
+    nuke_mine(&e, &test_data);
+
+    let not_enough_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_withdraw_prize(&xlm_client.address, &donor, &100_0000001)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(
+        not_enough_error,
+        ContractErrors::NotEnoughInPrizePool.into()
+    );
+
+    test_data.contract_client.mock_all_auths().withdraw_prize(
+        &xlm_client.address,
+        &donor,
+        &100_0000000,
+    );
+
+    assert_eq!(xlm_client.balance(&donor), 100_0000000);
+    assert_eq!(test_data.contract_client.prize_pool(&xlm_client.address), 0);
+}
@@ -0,0 +1,132 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::{Address, Env};
+
+use crate::contract::MAX_STAKE_REQUIREMENT;
+use crate::errors::ContractErrors;
+use crate::storage::{CurveBasis, CurveKind, RequirementCurve};
+use crate::tests::test_utils::{
+    create_test_data, mine_block, next_block_hash, start_contract, TestData,
+};
+
+#[test]
+fn test_requirement_curves() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    // By default one FCM for every 10_000 blocks
+    assert_eq!(test_data.contract_client.stake_requirement(&9_999), 0);
+    assert_eq!(
+        test_data.contract_client.stake_requirement(&85_000),
+        8_0000000
+    );
+
+    let invalid_step_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_set_requirement_curve(&RequirementCurve {
+            kind: CurveKind::Linear,
+            basis: CurveBasis::BlockIndex,
+            step: 0,
+            amount: 1_0000000,
+            growth_bps: 0,
+        })
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(
+        invalid_step_error,
+        ContractErrors::InvalidRequirementCurve.into()
+    );
+
+    let invalid_growth_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_set_requirement_curve(&RequirementCurve {
+            kind: CurveKind::Exponential,
+            basis: CurveBasis::BlockIndex,
+            step: 1_000,
+            amount: 1_0000000,
+            growth_bps: 10_001,
+        })
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(
+        invalid_growth_error,
+        ContractErrors::InvalidRequirementCurve.into()
+    );
+
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .set_requirement_curve(&RequirementCurve {
+            kind: CurveKind::Linear,
+            basis: CurveBasis::BlockIndex,
+            step: 10_000,
+            amount: 1_0000000,
+            growth_bps: 0,
+        });
+    assert_eq!(
+        test_data.contract_client.stake_requirement(&15_000),
+        1_5000000
+    );
+
+    // Doubles every 1_000 blocks
+    let curve: RequirementCurve = RequirementCurve {
+        kind: CurveKind::Exponential,
+        basis: CurveBasis::BlockIndex,
+        step: 1_000,
+        amount: 1_0000000,
+        growth_bps: 10_000,
+    };
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .set_requirement_curve(&curve);
+    assert_eq!(test_data.contract_client.requirement_curve(), curve);
+    assert_eq!(test_data.contract_client.stake_requirement(&999), 1_0000000);
+    assert_eq!(
+        test_data.contract_client.stake_requirement(&3_500),
+        8_0000000
+    );
+    assert_eq!(
+        test_data.contract_client.stake_requirement(&u64::MAX),
+        MAX_STAKE_REQUIREMENT
+    );
+}
+
+#[test]
+fn test_requirement_by_minted_units() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .set_requirement_curve(&RequirementCurve {
+            kind: CurveKind::Stepped,
+            basis: CurveBasis::MintedUnits,
+            step: 1,
+            amount: 1_0000000,
+            growth_bps: 0,
+        });
+
+    // The first block sends one FCM to the genesis miner, from there miners need one FCM staked
+    let miner: Address = Address::generate(&e);
+    mine_block(&e, &test_data, &miner);
+    assert_eq!(test_data.contract_client.total_minted(), 1_0000000);
+
+    let (hash, message, nonce) = next_block_hash(&e, &test_data, &miner);
+    let not_enough_staked_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_mine(&hash, &message, &nonce, &miner)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(
+        not_enough_staked_error,
+        ContractErrors::NotEnoughStaked.into()
+    );
+}
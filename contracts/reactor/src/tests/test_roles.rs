@@ -0,0 +1,199 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::{Address as _, BytesN as _, Events};
+use soroban_sdk::{symbol_short, vec, Address, BytesN, Env, IntoVal};
+
+use crate::errors::ContractErrors;
+use crate::storage::{get_state, set_role_member, ReactorState, Role, StorageKeys};
+use crate::tests::test_utils::{create_test_data, start_contract, TestData};
+
+#[test]
+fn test_finder_starts_with_every_role() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let finder: Address = test_data.genesis_block_miner.clone();
+    assert_eq!(test_data.contract_client.admin(), finder);
+    for role in [Role::DifficultyOperator, Role::Pauser, Role::Upgrader] {
+        assert!(test_data.contract_client.has_role(&role, &finder));
+    }
+}
+
+#[test]
+fn test_privileged_functions_reject_wrong_roles() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let operator: Address = Address::generate(&e);
+    let pauser: Address = Address::generate(&e);
+    let upgrader: Address = Address::generate(&e);
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .grant_role(&Role::DifficultyOperator, &operator);
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .grant_role(&Role::Pauser, &pauser);
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .grant_role(&Role::Upgrader, &upgrader);
+
+    for caller in [pauser.clone(), upgrader.clone()] {
+        let error = test_data
+            .contract_client
+            .mock_all_auths()
+            .try_set_difficulty(&caller, &3)
+            .unwrap_err()
+            .unwrap();
+        assert_eq!(error, ContractErrors::MissingRole.into());
+    }
+
+    for caller in [operator.clone(), pauser.clone()] {
+        let error = test_data
+            .contract_client
+            .mock_all_auths()
+            .try_schedule_upgrade(&caller, &BytesN::random(&e))
+            .unwrap_err()
+            .unwrap();
+        assert_eq!(error, ContractErrors::MissingRole.into());
+    }
+
+    for caller in [operator.clone(), upgrader.clone()] {
+        let error = test_data
+            .contract_client
+            .mock_all_auths()
+            .try_pause(&caller)
+            .unwrap_err()
+            .unwrap();
+        assert_eq!(error, ContractErrors::MissingRole.into());
+    }
+
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .set_difficulty(&operator, &3);
+    e.as_contract(&test_data.contract_client.address, || {
+        assert_eq!(get_state(&e).unwrap().difficulty, 3);
+    });
+
+    test_data.contract_client.mock_all_auths().pause(&pauser);
+    assert!(test_data.contract_client.is_paused());
+}
+
+#[test]
+fn test_revoke_role() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let finder: Address = test_data.genesis_block_miner.clone();
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .revoke_role(&Role::DifficultyOperator, &finder);
+    assert_eq!(
+        vec![&e, e.events().all().last().unwrap()],
+        vec![
+            &e,
+            (
+                test_data.contract_client.address.clone(),
+                (
+                    symbol_short!("role"),
+                    symbol_short!("revoked"),
+                    Role::DifficultyOperator,
+                )
+                    .into_val(&e),
+                finder.into_val(&e),
+            )
+        ]
+    );
+
+    // Being the admin doesn't give the roles
+    let error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_set_difficulty(&finder, &3)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(error, ContractErrors::MissingRole.into());
+}
+
+#[test]
+fn test_admin_transfer() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let finder: Address = test_data.genesis_block_miner.clone();
+    let new_admin: Address = Address::generate(&e);
+
+    let no_pending_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_accept_admin()
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(no_pending_error, ContractErrors::NoPendingAdmin.into());
+
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .propose_admin(&new_admin);
+    assert_eq!(
+        test_data.contract_client.pending_admin(),
+        Some(new_admin.clone())
+    );
+
+    // The transfer is only done once the new admin accepts it
+    assert_eq!(test_data.contract_client.admin(), finder);
+    test_data.contract_client.mock_all_auths().accept_admin();
+    assert_eq!(e.auths()[0].0, new_admin,);
+    assert_eq!(test_data.contract_client.admin(), new_admin);
+    assert_eq!(test_data.contract_client.pending_admin(), None);
+
+    // From now on the new admin is the one managing the reactor
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .set_cooling_period(&3600);
+    assert_eq!(e.auths()[0].0, new_admin);
+}
+
+#[test]
+fn test_legacy_finder_roles() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    // A reactor discovered before the roles existed only knows its finder
+    let finder: Address = test_data.genesis_block_miner.clone();
+    e.as_contract(&test_data.contract_client.address, || {
+        let state: ReactorState = get_state(&e).unwrap();
+        e.storage().instance().remove(&StorageKeys::Admin);
+        for role in [Role::DifficultyOperator, Role::Pauser, Role::Upgrader] {
+            set_role_member(&e, &role, &state.finder, false);
+        }
+    });
+
+    assert_eq!(test_data.contract_client.admin(), finder);
+    assert!(test_data.contract_client.has_role(&Role::Upgrader, &finder));
+
+    let operator: Address = Address::generate(&e);
+    let events_before: u32 = e.events().all().len();
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .grant_role(&Role::DifficultyOperator, &operator);
+
+    // The roles of the finder are written down the first time the roles are managed
+    assert!(test_data.contract_client.has_role(&Role::Upgrader, &finder));
+    assert!(test_data
+        .contract_client
+        .has_role(&Role::DifficultyOperator, &operator));
+    assert!(!test_data.contract_client.has_role(&Role::Pauser, &operator));
+    assert_eq!(e.events().all().len(), events_before + 4);
+}
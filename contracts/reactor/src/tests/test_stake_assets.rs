@@ -0,0 +1,212 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::{Address as _, Ledger};
+use soroban_sdk::{vec, Address, Env, Vec};
+
+use crate::contract::STAKE_COOLDOWN;
+use crate::errors::ContractErrors;
+use crate::storage::{
+    get_block, get_state, set_block, set_state, Block, ReactorState, StakeAsset, StakeLot,
+};
+use crate::tests::test_utils::{
+    create_test_data, create_token, mine_block, next_block_hash, start_contract, TestData,
+};
+
+#[test]
+fn test_set_stake_assets() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let (lp_share, _) = create_token(&e);
+
+    let fcm_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_set_stake_assets(&vec![
+            &e,
+            StakeAsset {
+                token: test_data.fcm_client.address.clone(),
+                weight: 1_0000000,
+            },
+        ])
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(fcm_error, ContractErrors::InvalidStakeAssets.into());
+
+    let repeated_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_set_stake_assets(&vec![
+            &e,
+            StakeAsset {
+                token: lp_share.address.clone(),
+                weight: 1_0000000,
+            },
+            StakeAsset {
+                token: lp_share.address.clone(),
+                weight: 2_0000000,
+            },
+        ])
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(repeated_error, ContractErrors::InvalidStakeAssets.into());
+
+    let assets = vec![
+        &e,
+        StakeAsset {
+            token: lp_share.address.clone(),
+            weight: 2_0000000,
+        },
+    ];
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .set_stake_assets(&assets);
+    assert_eq!(test_data.contract_client.stake_assets(), assets);
+}
+
+#[test]
+fn test_stake_assets_count_for_mining() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let (lp_share, lp_share_admin) = create_token(&e);
+    let (other_token, other_token_admin) = create_token(&e);
+    let miner: Address = Address::generate(&e);
+    lp_share_admin.mock_all_auths().mint(&miner, &4_0000000);
+    other_token_admin.mock_all_auths().mint(&miner, &4_0000000);
+
+    // Each LP share is worth 2 FCM
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .set_stake_assets(&vec![
+            &e,
+            StakeAsset {
+                token: lp_share.address.clone(),
+                weight: 2_0000000,
+            },
+        ]);
+
+    let not_accepted_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_stake_asset(&miner, &other_token.address, &4_0000000)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(
+        not_accepted_error,
+        ContractErrors::StakeAssetNotAccepted.into()
+    );
+
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .stake_asset(&miner, &lp_share.address, &4_0000000);
+    assert_eq!(test_data.contract_client.effective_stake(&miner), 8_0000000);
+    assert_eq!(
+        lp_share.balance(&test_data.contract_client.address),
+        4_0000000
+    );
+
+    // At this index the requirement is 8 FCM
+    e.as_contract(&test_data.contract_client.address, || {
+        let mut state: ReactorState = get_state(&e).unwrap();
+        let mut block: Block = get_block(&e, &state.current).unwrap();
+        state.current = 80_000;
+        block.index = state.current;
+        set_state(&e, &state);
+        set_block(&e, &block);
+    });
+
+    mine_block(&e, &test_data, &miner);
+
+    let still_hot_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_un_stake_asset(&miner, &lp_share.address, &4_0000000)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(still_hot_error, ContractErrors::StakeIsStillHot.into());
+
+    e.ledger().set_timestamp(STAKE_COOLDOWN + 1);
+    test_data.contract_client.mock_all_auths().un_stake_asset(
+        &miner,
+        &lp_share.address,
+        &4_0000000,
+    );
+    assert_eq!(lp_share.balance(&miner), 4_0000000);
+    assert_eq!(
+        test_data
+            .contract_client
+            .asset_stake_lots(&miner, &lp_share.address),
+        vec![&e]
+    );
+
+    let (hash, message, nonce) = next_block_hash(&e, &test_data, &miner);
+    let not_enough_staked_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_mine(&hash, &message, &nonce, &miner)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(
+        not_enough_staked_error,
+        ContractErrors::NotEnoughStaked.into()
+    );
+}
+
+#[test]
+fn test_delisted_asset_lots_survive_archival() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let (lp_share, lp_share_admin) = create_token(&e);
+    let miner: Address = Address::generate(&e);
+    lp_share_admin.mock_all_auths().mint(&miner, &4_0000000);
+
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .set_stake_assets(&vec![
+            &e,
+            StakeAsset {
+                token: lp_share.address.clone(),
+                weight: 2_0000000,
+            },
+        ]);
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .stake_asset(&miner, &lp_share.address, &4_0000000);
+
+    // The asset is delisted, its lots are still the miner's
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .set_stake_assets(&vec![&e]);
+
+    let pass_days = |days: u32| {
+        e.as_contract(&test_data.contract_client.address, || {
+            e.storage().instance().extend_ttl(17280 * 60, 17280 * 60);
+        });
+
+        e.ledger().with_mut(|ledger| {
+            ledger.sequence_number += 17280 * days;
+            ledger.timestamp += 3600 * 24 * days as u64;
+        });
+    };
+
+    pass_days(40);
+    test_data.contract_client.bump_stake(&miner);
+    pass_days(40);
+
+    let lots: Vec<StakeLot> = test_data
+        .contract_client
+        .asset_stake_lots(&miner, &lp_share.address);
+    assert_eq!(lots.len(), 1);
+    assert_eq!(lots.get(0).unwrap().amount, 4_0000000);
+}
@@ -0,0 +1,362 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::{Address as _, Ledger};
+use soroban_sdk::{token, vec, Address, Env};
+
+use crate::contract::{STAKE_COOLDOWN, VE_MAX_LOCK};
+use crate::errors::ContractErrors;
+use crate::storage::{
+    get_block, get_stake, get_state, set_block, set_stake, set_state, Block, ReactorState, Stake,
+    StakeLot, UnstakeQuote,
+};
+use crate::tests::test_utils::{
+    create_test_data, mine_block, next_block_hash, start_contract, TestData,
+};
+
+#[test]
+fn test_stake_lots_cool_down_separately() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let miner: Address = Address::generate(&e);
+    token::StellarAssetClient::new(&e, &test_data.fcm_client.address)
+        .mock_all_auths()
+        .mint(&miner, &8_0000000);
+
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .stake(&miner, &5_0000000);
+
+    e.ledger().set_timestamp(3600 * 24 * 10);
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .stake(&miner, &3_0000000);
+
+    assert_eq!(
+        test_data.contract_client.stake_lots(&miner),
+        vec![
+            &e,
+            StakeLot {
+                amount: 5_0000000,
+                cools_at: STAKE_COOLDOWN,
+            },
+            StakeLot {
+                amount: 3_0000000,
+                cools_at: 3600 * 24 * 10 + STAKE_COOLDOWN,
+            },
+        ]
+    );
+
+    let still_hot_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_un_stake(&miner, &5_0000000)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(still_hot_error, ContractErrors::StakeIsStillHot.into());
+
+    // Every lot counts for the mining requirement
+    e.as_contract(&test_data.contract_client.address, || {
+        let mut state: ReactorState = get_state(&e).unwrap();
+        let mut block: Block = get_block(&e, &state.current).unwrap();
+        state.current = 80_000;
+        block.index = state.current;
+        set_state(&e, &state);
+        set_block(&e, &block);
+    });
+    e.ledger().set_timestamp(STAKE_COOLDOWN + 1);
+    mine_block(&e, &test_data, &miner);
+
+    // Only the first lot cooled down, the second one keeps its own cooldown
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .un_stake(&miner, &5_0000000);
+    assert_eq!(test_data.fcm_client.balance(&miner), 5_0000000);
+    assert_eq!(
+        test_data.contract_client.stake_lots(&miner),
+        vec![
+            &e,
+            StakeLot {
+                amount: 3_0000000,
+                cools_at: 3600 * 24 * 10 + STAKE_COOLDOWN,
+            },
+        ]
+    );
+
+    let (hash, message, nonce) = next_block_hash(&e, &test_data, &miner);
+    let not_enough_staked_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_mine(&hash, &message, &nonce, &miner)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(
+        not_enough_staked_error,
+        ContractErrors::NotEnoughStaked.into()
+    );
+}
+
+#[test]
+fn test_legacy_stake_migration() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let miner: Address = Address::generate(&e);
+    token::StellarAssetClient::new(&e, &test_data.fcm_client.address)
+        .mock_all_auths()
+        .mint(&test_data.contract_client.address, &4_0000000);
+    token::StellarAssetClient::new(&e, &test_data.fcm_client.address)
+        .mock_all_auths()
+        .mint(&miner, &1_0000000);
+
+    e.as_contract(&test_data.contract_client.address, || {
+        set_stake(
+            &e,
+            &Stake {
+                owner: miner.clone(),
+                amount: 4_0000000,
+                cools_at: 100,
+            },
+        );
+    });
+
+    assert_eq!(
+        test_data.contract_client.stake_lots(&miner),
+        vec![
+            &e,
+            StakeLot {
+                amount: 4_0000000,
+                cools_at: 100,
+            },
+        ]
+    );
+
+    // A new deposit doesn't restart the cooldown of the legacy stake
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .stake(&miner, &1_0000000);
+
+    e.as_contract(&test_data.contract_client.address, || {
+        assert!(get_stake(&e, &miner).is_none());
+    });
+    assert_eq!(test_data.contract_client.stake_lots(&miner).len(), 2);
+
+    e.ledger().set_timestamp(101);
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .un_stake(&miner, &4_0000000);
+    assert_eq!(test_data.fcm_client.balance(&miner), 4_0000000);
+    assert_eq!(
+        test_data.contract_client.stake_lots(&miner),
+        vec![
+            &e,
+            StakeLot {
+                amount: 1_0000000,
+                cools_at: STAKE_COOLDOWN,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_partial_un_stake() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let miner: Address = Address::generate(&e);
+    token::StellarAssetClient::new(&e, &test_data.fcm_client.address)
+        .mock_all_auths()
+        .mint(&miner, &10_0000000);
+
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .stake(&miner, &10_0000000);
+
+    // The miner joins the attempt of a block that needs 8 FCM staked
+    e.ledger().set_timestamp(STAKE_COOLDOWN + 1);
+    e.as_contract(&test_data.contract_client.address, || {
+        let mut state: ReactorState = get_state(&e).unwrap();
+        let mut block: Block = get_block(&e, &state.current).unwrap();
+        state.current = 80_000;
+        block.index = state.current;
+        block.timestamp = e.ledger().timestamp();
+        set_state(&e, &state);
+        set_block(&e, &block);
+    });
+
+    let (hash, message, nonce) = next_block_hash(&e, &test_data, &miner);
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .mine(&hash, &message, &nonce, &miner);
+
+    let below_requirement_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_un_stake(&miner, &3_0000000)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(
+        below_requirement_error,
+        ContractErrors::StakeBelowRequirement.into()
+    );
+
+    let too_much_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_un_stake(&miner, &10_0000001)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(too_much_error, ContractErrors::StakeIsStillHot.into());
+
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .un_stake(&miner, &2_0000000);
+    assert_eq!(test_data.fcm_client.balance(&miner), 2_0000000);
+    assert_eq!(
+        test_data.contract_client.stake_lots(&miner),
+        vec![
+            &e,
+            StakeLot {
+                amount: 8_0000000,
+                cools_at: STAKE_COOLDOWN,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_emergency_unstake_burns_a_penalty() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let miner: Address = Address::generate(&e);
+    token::StellarAssetClient::new(&e, &test_data.fcm_client.address)
+        .mock_all_auths()
+        .mint(&miner, &20_0000000);
+
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .stake(&miner, &10_0000000);
+
+    e.ledger().set_timestamp(STAKE_COOLDOWN / 2);
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .stake(&miner, &10_0000000);
+
+    // Half of the first lot is burned and the whole second lot because it was just staked
+    let quote: UnstakeQuote = test_data.contract_client.emergency_unstake_quote(&miner);
+    assert_eq!(
+        quote,
+        UnstakeQuote {
+            amount: 5_0000000,
+            penalty: 15_0000000,
+        }
+    );
+
+    e.ledger().set_timestamp(STAKE_COOLDOWN);
+    let expected_quote: UnstakeQuote = UnstakeQuote {
+        amount: 15_0000000,
+        penalty: 5_0000000,
+    };
+    assert_eq!(
+        test_data.contract_client.emergency_unstake_quote(&miner),
+        expected_quote
+    );
+
+    let result: UnstakeQuote = test_data
+        .contract_client
+        .mock_all_auths()
+        .emergency_unstake(&miner);
+    assert_eq!(result, expected_quote);
+
+    assert_eq!(test_data.fcm_client.balance(&miner), 15_0000000);
+    assert_eq!(
+        test_data
+            .fcm_client
+            .balance(&test_data.contract_client.address),
+        0
+    );
+    assert!(test_data.contract_client.stake_lots(&miner).is_empty());
+
+    let nothing_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_emergency_unstake(&miner)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(nothing_error, ContractErrors::NothingToWithdraw.into());
+}
+
+#[test]
+fn test_stake_survives_archival() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let miner: Address = Address::generate(&e);
+    token::StellarAssetClient::new(&e, &test_data.fcm_client.address)
+        .mock_all_auths()
+        .mint(&miner, &7_0000000);
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .stake(&miner, &5_0000000);
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .ve_lock(&miner, &2_0000000, &VE_MAX_LOCK);
+
+    let pass_days = |days: u32| {
+        // The reactor itself is kept alive by the activity of every miner
+        e.as_contract(&test_data.contract_client.address, || {
+            e.storage().instance().extend_ttl(17280 * 60, 17280 * 60);
+        });
+
+        e.ledger().with_mut(|ledger| {
+            ledger.sequence_number += 17280 * days;
+            ledger.timestamp += 3600 * 24 * days as u64;
+        });
+    };
+
+    pass_days(50);
+
+    // Anyone can bump a stake, no auth is needed
+    test_data.contract_client.bump_stake(&miner);
+
+    pass_days(50);
+
+    assert_eq!(
+        test_data.contract_client.stake_lots(&miner),
+        vec![
+            &e,
+            StakeLot {
+                amount: 5_0000000,
+                cools_at: STAKE_COOLDOWN,
+            },
+        ]
+    );
+
+    // The lock lasts for years, bumping the stake keeps it alive too
+    assert_eq!(
+        test_data.contract_client.ve_lock_of(&miner).unwrap().amount,
+        2_0000000
+    );
+
+    // Reading the stake bumps it again
+    pass_days(50);
+    assert_eq!(test_data.contract_client.stake_lots(&miner).len(), 1);
+}
@@ -0,0 +1,126 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::{token, vec, Address, Env};
+
+use crate::errors::ContractErrors;
+use crate::storage::{set_stake_lots, StakeLot};
+use crate::tests::test_utils::{create_test_data, mine_block, start_contract, TestData};
+
+#[test]
+fn test_staking_rewards_from_emission() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let genesis_miner: Address = test_data.genesis_block_miner.clone();
+    let first_staker: Address = Address::generate(&e);
+    let second_staker: Address = Address::generate(&e);
+    token::StellarAssetClient::new(&e, &test_data.fcm_client.address)
+        .mock_all_auths()
+        .mint(&first_staker, &3_0000000);
+    token::StellarAssetClient::new(&e, &test_data.fcm_client.address)
+        .mock_all_auths()
+        .mint(&second_staker, &1_0000000);
+
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .stake(&first_staker, &3_0000000);
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .stake(&second_staker, &1_0000000);
+    assert_eq!(test_data.contract_client.total_staked(), 4_0000000);
+
+    let invalid_cut_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_set_staking_cut(&5_001)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(invalid_cut_error, ContractErrors::InvalidStakingCut.into());
+
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .set_staking_cut(&5_000);
+    assert_eq!(test_data.contract_client.staking_cut(), 5_000);
+
+    // The genesis miner wins the first block, half of it goes to the stakers
+    mine_block(&e, &test_data, &Address::generate(&e));
+
+    assert_eq!(test_data.fcm_client.balance(&genesis_miner), 5000000);
+    assert_eq!(
+        test_data.contract_client.staking_rewards(&first_staker),
+        3750000
+    );
+    assert_eq!(
+        test_data.contract_client.staking_rewards(&second_staker),
+        1250000
+    );
+
+    assert_eq!(
+        test_data
+            .contract_client
+            .mock_all_auths()
+            .claim_staking_rewards(&first_staker),
+        3750000
+    );
+    assert_eq!(test_data.fcm_client.balance(&first_staker), 3750000);
+    assert_eq!(test_data.contract_client.staking_rewards(&first_staker), 0);
+
+    let nothing_to_claim_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_claim_staking_rewards(&first_staker)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(
+        nothing_to_claim_error,
+        ContractErrors::NothingToWithdraw.into()
+    );
+}
+
+#[test]
+fn test_stakers_registered_lazily() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    // A stake from before the reactor tracked the total staked
+    let staker: Address = Address::generate(&e);
+    e.as_contract(&test_data.contract_client.address, || {
+        set_stake_lots(
+            &e,
+            &staker,
+            &vec![
+                &e,
+                StakeLot {
+                    amount: 2_0000000,
+                    cools_at: 0,
+                },
+            ],
+        );
+    });
+    assert_eq!(test_data.contract_client.total_staked(), 0);
+
+    // The legacy stake is registered the next time the staker touches its stake
+    token::StellarAssetClient::new(&e, &test_data.fcm_client.address)
+        .mock_all_auths()
+        .mint(&staker, &1_0000000);
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .stake(&staker, &1_0000000);
+    assert_eq!(test_data.contract_client.total_staked(), 3_0000000);
+
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .set_staking_cut(&5_000);
+    mine_block(&e, &test_data, &Address::generate(&e));
+
+    // Rewards are rounded down in favor of the reactor
+    assert_eq!(test_data.contract_client.staking_rewards(&staker), 4999999);
+}
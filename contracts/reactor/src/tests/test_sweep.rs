@@ -0,0 +1,167 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::{Address as _, Events, Ledger};
+use soroban_sdk::{symbol_short, token, vec, Address, Env, IntoVal};
+
+use crate::contract::STAKE_COOLDOWN;
+use crate::errors::{ContractErrors, SweepErrors};
+use crate::storage::{get_held_fcm, StakeAsset, StorageKeys};
+use crate::tests::test_utils::{
+    create_test_data, create_token, mine_block, start_contract, TestData,
+};
+
+#[test]
+fn test_sweep_a_stray_token() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let client = test_data.contract_client.mock_all_auths();
+    let recipient: Address = Address::generate(&e);
+    let (stray, stray_admin) = create_token(&e);
+    stray_admin
+        .mock_all_auths()
+        .mint(&test_data.contract_client.address, &5_0000000);
+    assert_eq!(client.sweepable(&stray.address), 5_0000000);
+
+    let zero_error = client
+        .try_sweep(&stray.address, &recipient, &0)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(zero_error, ContractErrors::InvalidAmount.into());
+
+    let too_much_error = client
+        .try_sweep(&stray.address, &recipient, &5_0000001)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(too_much_error, SweepErrors::SweepExceedsSurplus.into());
+
+    client.sweep(&stray.address, &recipient, &5_0000000);
+    assert_eq!(
+        vec![&e, e.events().all().last().unwrap()],
+        vec![
+            &e,
+            (
+                test_data.contract_client.address.clone(),
+                (symbol_short!("sweep"), stray.address.clone()).into_val(&e),
+                (recipient.clone(), 5_0000000u128).into_val(&e),
+            ),
+        ]
+    );
+    assert_eq!(stray.balance(&recipient), 5_0000000);
+    assert_eq!(client.sweepable(&stray.address), 0);
+}
+
+#[test]
+fn test_staked_funds_cant_be_swept() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let client = test_data.contract_client.mock_all_auths();
+    let staker: Address = Address::generate(&e);
+    let recipient: Address = Address::generate(&e);
+    let (lp_share, lp_share_admin) = create_token(&e);
+    let fcm_admin = token::StellarAssetClient::new(&e, &test_data.fcm_client.address);
+    fcm_admin.mock_all_auths().mint(&staker, &30_0000000);
+    lp_share_admin.mock_all_auths().mint(&staker, &10_0000000);
+
+    client.set_stake_assets(&vec![
+        &e,
+        StakeAsset {
+            token: lp_share.address.clone(),
+            weight: 1_0000000,
+        },
+    ]);
+    client.set_prize_pool(&vec![&e, lp_share.address.clone()], &100);
+    client.set_staking_cut(&5_000);
+
+    client.stake(&staker, &10_0000000);
+    client.ve_lock(&staker, &10_0000000, &STAKE_COOLDOWN);
+    client.stake_asset(&staker, &lp_share.address, &6_0000000);
+    client.deposit_prize(&staker, &lp_share.address, &4_0000000);
+    mine_block(&e, &test_data, &Address::generate(&e));
+    let prize: u128 = client.prize_pool(&lp_share.address);
+
+    // Only what was sent by mistake can leave the reactor
+    fcm_admin
+        .mock_all_auths()
+        .mint(&test_data.contract_client.address, &3_0000000);
+    assert_eq!(client.sweepable(&test_data.fcm_client.address), 3_0000000);
+    assert_eq!(client.sweepable(&lp_share.address), 0);
+
+    for (token, amount) in [
+        (test_data.fcm_client.address.clone(), 3_0000001),
+        (lp_share.address.clone(), 1),
+    ] {
+        let error = client
+            .try_sweep(&token, &recipient, &amount)
+            .unwrap_err()
+            .unwrap();
+        assert_eq!(error, SweepErrors::SweepExceedsSurplus.into());
+    }
+
+    client.sweep(&test_data.fcm_client.address, &recipient, &3_0000000);
+    assert_eq!(test_data.fcm_client.balance(&recipient), 3_0000000);
+    assert_eq!(client.sweepable(&test_data.fcm_client.address), 0);
+
+    // Every staker still gets everything back
+    let rewards: u128 = client.claim_staking_rewards(&staker);
+    assert!(rewards > 0);
+    e.ledger().set_timestamp(STAKE_COOLDOWN + 1);
+    client.un_stake(&staker, &10_0000000);
+    client.ve_withdraw(&staker);
+    client.un_stake_asset(&staker, &lp_share.address, &6_0000000);
+    assert_eq!(
+        test_data.fcm_client.balance(&staker),
+        30_0000000 + rewards as i128
+    );
+    assert_eq!(lp_share.balance(&staker), 6_0000000);
+    assert_eq!(client.prize_pool(&lp_share.address), prize);
+    assert_eq!(
+        lp_share.balance(&test_data.contract_client.address),
+        prize as i128
+    );
+    e.as_contract(&test_data.contract_client.address, || {
+        assert_eq!(get_held_fcm(&e), Some(0));
+    });
+}
+
+#[test]
+fn test_untracked_fcm_cant_be_swept_until_migrated() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let client = test_data.contract_client.mock_all_auths();
+    let staker: Address = Address::generate(&e);
+    let recipient: Address = Address::generate(&e);
+    let fcm_admin = token::StellarAssetClient::new(&e, &test_data.fcm_client.address);
+    fcm_admin.mock_all_auths().mint(&staker, &10_0000000);
+    client.stake(&staker, &10_0000000);
+
+    // A reactor from before the tracking, with its schema version 2
+    e.as_contract(&test_data.contract_client.address, || {
+        e.storage().instance().remove(&StorageKeys::HeldFcm);
+        e.storage()
+            .instance()
+            .set(&StorageKeys::SchemaVersion, &2u32);
+    });
+    fcm_admin
+        .mock_all_auths()
+        .mint(&test_data.contract_client.address, &1_0000000);
+
+    assert_eq!(client.sweepable(&test_data.fcm_client.address), 0);
+    let untracked_error = client
+        .try_sweep(&test_data.fcm_client.address, &recipient, &1_0000000)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(untracked_error, SweepErrors::FcmIsNotTracked.into());
+
+    // Everything the reactor has when it's migrated is considered the users'
+    client.migrate(&test_data.genesis_block_miner);
+    e.as_contract(&test_data.contract_client.address, || {
+        assert_eq!(get_held_fcm(&e), Some(11_0000000));
+    });
+    assert_eq!(client.sweepable(&test_data.fcm_client.address), 0);
+}
@@ -0,0 +1,177 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::{Address as _, BytesN as _, Events, Ledger};
+use soroban_sdk::{symbol_short, token, vec, Address, BytesN, Env, IntoVal};
+
+use crate::contract::{STAKE_COOLDOWN, UPGRADE_DELAY};
+use crate::errors::ContractErrors;
+use crate::storage::{PendingUpgrade, Role};
+use crate::tests::test_utils::{create_test_data, start_contract, TestData};
+
+#[test]
+fn test_schedule_and_cancel_upgrade() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let upgrader: Address = Address::generate(&e);
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .grant_role(&Role::Upgrader, &upgrader);
+
+    let no_upgrade_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_cancel_upgrade(&upgrader)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(no_upgrade_error, ContractErrors::NoUpgradeScheduled.into());
+
+    e.ledger().set_timestamp(1_000);
+    let hash: BytesN<32> = BytesN::random(&e);
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .schedule_upgrade(&upgrader, &hash);
+    assert_eq!(
+        vec![&e, e.events().all().last().unwrap()],
+        vec![
+            &e,
+            (
+                test_data.contract_client.address.clone(),
+                (symbol_short!("upgrade"), symbol_short!("scheduled")).into_val(&e),
+                (hash.clone(), 1_000 + UPGRADE_DELAY).into_val(&e),
+            )
+        ]
+    );
+    assert_eq!(
+        test_data.contract_client.pending_upgrade(),
+        Some(PendingUpgrade {
+            hash: hash.clone(),
+            executable_at: 1_000 + UPGRADE_DELAY,
+        })
+    );
+
+    let already_scheduled_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_schedule_upgrade(&upgrader, &BytesN::random(&e))
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(
+        already_scheduled_error,
+        ContractErrors::UpgradeAlreadyScheduled.into()
+    );
+
+    // Miners have the whole delay to react before the upgrade can be executed
+    e.ledger().set_timestamp(1_000 + UPGRADE_DELAY - 1);
+    let time_locked_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_execute_upgrade(&upgrader)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(
+        time_locked_error,
+        ContractErrors::UpgradeIsTimeLocked.into()
+    );
+
+    let missing_role_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_cancel_upgrade(&Address::generate(&e))
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(missing_role_error, ContractErrors::MissingRole.into());
+
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .cancel_upgrade(&upgrader);
+    assert_eq!(test_data.contract_client.pending_upgrade(), None);
+    assert_eq!(
+        vec![&e, e.events().all().last().unwrap()],
+        vec![
+            &e,
+            (
+                test_data.contract_client.address.clone(),
+                (symbol_short!("upgrade"), symbol_short!("canceled")).into_val(&e),
+                hash.into_val(&e),
+            )
+        ]
+    );
+
+    e.ledger().set_timestamp(1_000 + UPGRADE_DELAY);
+    let canceled_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_execute_upgrade(&upgrader)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(canceled_error, ContractErrors::NoUpgradeScheduled.into());
+}
+
+#[test]
+fn test_stakers_can_leave_before_an_upgrade() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let upgrader: Address = Address::generate(&e);
+    let staker: Address = Address::generate(&e);
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .grant_role(&Role::Upgrader, &upgrader);
+    token::StellarAssetClient::new(&e, &test_data.fcm_client.address)
+        .mock_all_auths()
+        .mint(&staker, &10_0000000);
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .stake(&staker, &10_0000000);
+
+    // Half way through the cooldown the stake can't be taken yet
+    e.ledger().set_timestamp(STAKE_COOLDOWN / 2);
+    let still_hot_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_un_stake(&staker, &5_0000000)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(still_hot_error, ContractErrors::StakeIsStillHot.into());
+
+    // Once an upgrade is announced the staker can leave before it runs, without penalties
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .schedule_upgrade(&upgrader, &BytesN::random(&e));
+    e.ledger()
+        .set_timestamp(STAKE_COOLDOWN / 2 + UPGRADE_DELAY - 1);
+    assert_eq!(
+        test_data
+            .contract_client
+            .emergency_unstake_quote(&staker)
+            .penalty,
+        0
+    );
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .un_stake(&staker, &5_0000000);
+    assert_eq!(test_data.fcm_client.balance(&staker), 5_0000000);
+
+    // Without the upgrade the cooldown applies again
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .cancel_upgrade(&upgrader);
+    let cooling_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_un_stake(&staker, &5_0000000)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(cooling_error, ContractErrors::StakeIsStillHot.into());
+}
@@ -1,9 +1,13 @@
 #![cfg(test)]
 
-use soroban_sdk::testutils::Address as _;
-use soroban_sdk::{token, Address, Env, String};
+use soroban_sdk::testutils::{Address as _, Ledger};
+use soroban_sdk::{token, vec, Address, BytesN, Env, String};
 
-use crate::contract::{ReactorContract, ReactorContractClient};
+use crate::contract::{
+    create_block_hash, is_difficulty_correct, ReactorContract, ReactorContractClient,
+    NUKE_GRACE_PERIOD,
+};
+use crate::storage::{get_block, get_state, ReactorConfig};
 
 pub struct TestData<'a> {
     pub contract_client: ReactorContractClient<'a>,
@@ -11,13 +15,22 @@ pub struct TestData<'a> {
     pub genesis_block_miner: Address,
 }
 
+pub fn deployer_salt(e: &Env) -> BytesN<32> {
+    BytesN::from_array(e, &[7u8; 32])
+}
+
+// The reactor is registered at the address its admin (the genesis block miner) would deploy it to
 pub fn create_test_data<'a>(e: &Env) -> TestData<'a> {
-    let contract_id: Address = e.register_contract(None, ReactorContract);
-    let contract_client: ReactorContractClient<'a> = ReactorContractClient::new(&e, &contract_id);
+    let genesis_block_miner: Address = Address::generate(e);
+    let deployed_address: Address = e
+        .deployer()
+        .with_address(genesis_block_miner.clone(), deployer_salt(e))
+        .deployed_address();
+    let contract_id: Address = e.register_contract(&deployed_address, ReactorContract);
+    let contract_client: ReactorContractClient<'a> = ReactorContractClient::new(e, &contract_id);
 
     let contract_address = e.register_stellar_asset_contract_v2(contract_client.address.clone());
     let fcm_client = token::Client::new(e, &contract_address.address());
-    let genesis_block_miner: Address = Address::generate(&e);
 
     TestData {
         contract_client,
@@ -26,11 +39,94 @@ pub fn create_test_data<'a>(e: &Env) -> TestData<'a> {
     }
 }
 
+pub fn reactor_config(e: &Env, test_data: &TestData) -> ReactorConfig {
+    ReactorConfig {
+        fcm: test_data.fcm_client.address.clone(),
+        genesis_message: String::from_str(e, "Hello World!"),
+        difficulty: 0,
+    }
+}
+
 pub fn start_contract(e: &Env, test_data: &TestData) {
-    let message: String = String::from_str(&e, "Hello World!");
-    test_data.contract_client.mock_all_auths().find(
-        &test_data.fcm_client.address,
+    test_data.contract_client.mock_all_auths().initialize(
         &test_data.genesis_block_miner,
-        &message,
+        &deployer_salt(e),
+        &reactor_config(e, test_data),
     );
 }
+
+// Finds a valid hash (with its message and nonce) for the next block
+pub fn next_block_hash(
+    e: &Env,
+    test_data: &TestData,
+    miner: &Address,
+) -> (BytesN<32>, String, u64) {
+    let message: String = String::from_str(e, "Mining");
+    let (current, difficulty, prev_hash): (u64, u32, BytesN<32>) =
+        e.as_contract(&test_data.contract_client.address, || {
+            let state = get_state(e).unwrap();
+            let prev_hash: BytesN<32> = get_block(e, &state.current).unwrap().hash;
+            (state.current, state.difficulty, prev_hash)
+        });
+
+    let nonce: u64 = find_nonce(e, &(current + 1), &message, &prev_hash, &difficulty, miner);
+    let hash: BytesN<32> =
+        create_block_hash(e, &(current + 1), &message, &prev_hash, &nonce, miner);
+
+    (hash, message, nonce)
+}
+
+// Finds the first nonce that gives a hash with the expected difficulty
+pub fn find_nonce(
+    e: &Env,
+    index: &u64,
+    message: &String,
+    prev_hash: &BytesN<32>,
+    difficulty: &u32,
+    miner: &Address,
+) -> u64 {
+    let mut nonce: u64 = 0;
+    while !is_difficulty_correct(
+        &create_block_hash(e, index, message, prev_hash, &nonce, miner),
+        difficulty,
+    ) {
+        nonce += 1;
+    }
+    nonce
+}
+
+// Mines the next block with the given miner, moving the ledger forward so the block gets closed
+pub fn mine_block(e: &Env, test_data: &TestData, miner: &Address) {
+    let (hash, message, nonce) = next_block_hash(e, test_data, miner);
+    e.ledger().set_timestamp(e.ledger().timestamp() + 61);
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .mine(&hash, &message, &nonce, miner);
+}
+
+pub fn create_token<'a>(e: &Env) -> (token::Client<'a>, token::StellarAssetClient<'a>) {
+    let address: Address = e
+        .register_stellar_asset_contract_v2(Address::generate(e))
+        .address();
+    (
+        token::Client::new(e, &address),
+        token::StellarAssetClient::new(e, &address),
+    )
+}
+
+// Nukes the mine with the genesis block miner as the only guardian, waiting out the grace period
+pub fn nuke_mine(e: &Env, test_data: &TestData) {
+    let guardians = vec![e, test_data.genesis_block_miner.clone()];
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .set_guardians(&vec![e], &guardians, &1);
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .fkin_nuke_it(&guardians);
+    e.ledger()
+        .set_timestamp(e.ledger().timestamp() + NUKE_GRACE_PERIOD);
+    test_data.contract_client.detonate_nuke();
+}
@@ -0,0 +1,165 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::{Address as _, Ledger};
+use soroban_sdk::{token, Address, Env};
+
+use crate::contract::{STAKE_COOLDOWN, VE_MAX_LOCK};
+use crate::errors::ContractErrors;
+use crate::storage::{
+    get_attempt, get_block, get_state, set_block, set_state, Block, ReactorState, VeLock,
+};
+use crate::tests::test_utils::{create_test_data, mine_block, start_contract, TestData};
+
+#[test]
+fn test_ve_lock_power_decays() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let owner: Address = Address::generate(&e);
+    token::StellarAssetClient::new(&e, &test_data.fcm_client.address)
+        .mock_all_auths()
+        .mint(&owner, &20_0000000);
+
+    let too_short_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_ve_lock(&owner, &10_0000000, &(STAKE_COOLDOWN - 1))
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(too_short_error, ContractErrors::InvalidLockDuration.into());
+
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .ve_lock(&owner, &10_0000000, &(STAKE_COOLDOWN * 2));
+    assert_eq!(
+        test_data.contract_client.effective_stake(&owner),
+        20_0000000
+    );
+
+    let already_exists_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_ve_lock(&owner, &10_0000000, &STAKE_COOLDOWN)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(
+        already_exists_error,
+        ContractErrors::VeLockAlreadyExists.into()
+    );
+
+    e.ledger().set_timestamp(STAKE_COOLDOWN);
+    assert_eq!(
+        test_data.contract_client.effective_stake(&owner),
+        10_0000000
+    );
+
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .ve_increase(&owner, &10_0000000);
+    assert_eq!(
+        test_data.contract_client.effective_stake(&owner),
+        20_0000000
+    );
+
+    let invalid_extension_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_ve_extend(&owner, &(VE_MAX_LOCK + 1))
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(
+        invalid_extension_error,
+        ContractErrors::InvalidLockDuration.into()
+    );
+
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .ve_extend(&owner, &(STAKE_COOLDOWN * 4));
+    assert_eq!(
+        test_data.contract_client.effective_stake(&owner),
+        80_0000000
+    );
+    assert_eq!(
+        test_data.contract_client.ve_lock_of(&owner),
+        Some(VeLock {
+            owner: owner.clone(),
+            amount: 20_0000000,
+            unlocks_at: STAKE_COOLDOWN * 5,
+        })
+    );
+
+    let still_locked_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_ve_withdraw(&owner)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(still_locked_error, ContractErrors::StakeIsStillHot.into());
+
+    e.ledger().set_timestamp(STAKE_COOLDOWN * 5);
+    assert_eq!(test_data.contract_client.effective_stake(&owner), 0);
+
+    let expired_error = test_data
+        .contract_client
+        .mock_all_auths()
+        .try_ve_increase(&owner, &1)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(expired_error, ContractErrors::VeLockExpired.into());
+
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .ve_withdraw(&owner);
+    assert_eq!(test_data.fcm_client.balance(&owner), 20_0000000);
+    assert!(test_data.contract_client.ve_lock_of(&owner).is_none());
+}
+
+#[test]
+fn test_ve_lock_counts_for_mining() {
+    let e: Env = Env::default();
+    let test_data: TestData = create_test_data(&e);
+    start_contract(&e, &test_data);
+
+    let miner: Address = Address::generate(&e);
+    let whale: Address = Address::generate(&e);
+    token::StellarAssetClient::new(&e, &test_data.fcm_client.address)
+        .mock_all_auths()
+        .mint(&miner, &4_0000000);
+    token::StellarAssetClient::new(&e, &test_data.fcm_client.address)
+        .mock_all_auths()
+        .mint(&whale, &200_0000000);
+
+    // 4 FCM locked for (a bit more than) twice the cooldown cover the 8 FCM required
+    test_data.contract_client.mock_all_auths().ve_lock(
+        &miner,
+        &4_0000000,
+        &(STAKE_COOLDOWN * 2 + 3600),
+    );
+    test_data
+        .contract_client
+        .mock_all_auths()
+        .ve_lock(&whale, &200_0000000, &(STAKE_COOLDOWN * 2));
+
+    e.as_contract(&test_data.contract_client.address, || {
+        let mut state: ReactorState = get_state(&e).unwrap();
+        let mut block: Block = get_block(&e, &state.current).unwrap();
+        state.current = 80_000;
+        block.index = state.current;
+        set_state(&e, &state);
+        set_block(&e, &block);
+    });
+
+    mine_block(&e, &test_data, &miner);
+    mine_block(&e, &test_data, &whale);
+
+    // The whale gets the max bonus for having more than twice VE_TICKET_STAKE of power
+    e.as_contract(&test_data.contract_client.address, || {
+        assert_eq!(get_attempt(&e, &80_001).unwrap().total_miners, 1);
+        assert_eq!(get_attempt(&e, &80_002).unwrap().total_miners, 3);
+    });
+}
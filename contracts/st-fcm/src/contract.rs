@@ -0,0 +1,216 @@
+use soroban_sdk::token::TokenInterface;
+use soroban_sdk::{
+    contract, contractimpl, panic_with_error, symbol_short, Address, BytesN, Env, String,
+};
+
+use crate::errors::ContractErrors;
+use crate::storage::{
+    get_admin, get_allowance, get_balance, pump_balance, pump_core, set_admin, set_allowance,
+    set_balance, AllowanceValue,
+};
+
+pub const DECIMALS: u32 = 7;
+pub const NAME: &str = "Staked FCM";
+pub const SYMBOL: &str = "stFCM";
+
+// Receipts of the FCM staked in the reactor, they are minted when someone stakes and redeemed
+// (burned by the reactor) when the stake is taken back
+pub trait StakedFcmContractTrait {
+    fn initialize(e: Env, deployer: Address, salt: BytesN<32>, admin: Address);
+
+    fn admin(e: Env) -> Address;
+
+    fn mint(e: Env, to: Address, amount: i128);
+
+    fn clawback(e: Env, from: Address, amount: i128);
+}
+
+#[contract]
+pub struct StakedFcmContract;
+
+#[contractimpl]
+impl StakedFcmContractTrait for StakedFcmContract {
+    // Only the account that deployed the token can initialize it (it passes the salt it used for
+    // the deployment), so no one can take the admin before it
+    fn initialize(e: Env, deployer: Address, salt: BytesN<32>, admin: Address) {
+        deployer.require_auth();
+
+        if get_admin(&e).is_some() {
+            panic_with_error!(&e, &ContractErrors::AlreadyInitialized);
+        }
+
+        if e.deployer().with_address(deployer, salt).deployed_address()
+            != e.current_contract_address()
+        {
+            panic_with_error!(&e, &ContractErrors::NotTheDeployer);
+        }
+
+        set_admin(&e, &admin);
+        pump_core(&e);
+    }
+
+    fn admin(e: Env) -> Address {
+        get_admin(&e).unwrap_or_else(|| {
+            panic_with_error!(&e, &ContractErrors::NotInitialized);
+        })
+    }
+
+    fn mint(e: Env, to: Address, amount: i128) {
+        check_amount(&e, &amount);
+        Self::admin(e.clone()).require_auth();
+
+        receive_balance(&e, &to, &amount);
+        pump_core(&e);
+
+        e.events()
+            .publish((symbol_short!("mint"), Self::admin(e.clone()), to), amount);
+    }
+
+    fn clawback(e: Env, from: Address, amount: i128) {
+        check_amount(&e, &amount);
+        Self::admin(e.clone()).require_auth();
+
+        spend_balance(&e, &from, &amount);
+        pump_core(&e);
+
+        e.events().publish(
+            (symbol_short!("clawback"), Self::admin(e.clone()), from),
+            amount,
+        );
+    }
+}
+
+#[contractimpl]
+impl TokenInterface for StakedFcmContract {
+    fn allowance(e: Env, from: Address, spender: Address) -> i128 {
+        get_allowance(&e, &from, &spender).amount
+    }
+
+    fn approve(e: Env, from: Address, spender: Address, amount: i128, expiration_ledger: u32) {
+        from.require_auth();
+        check_amount(&e, &amount);
+
+        if amount > 0 && expiration_ledger < e.ledger().sequence() {
+            panic_with_error!(&e, &ContractErrors::InvalidExpirationLedger);
+        }
+
+        set_allowance(
+            &e,
+            &from,
+            &spender,
+            &AllowanceValue {
+                amount,
+                expiration_ledger,
+            },
+        );
+        pump_core(&e);
+
+        e.events().publish(
+            (symbol_short!("approve"), from, spender),
+            (amount, expiration_ledger),
+        );
+    }
+
+    fn balance(e: Env, id: Address) -> i128 {
+        pump_balance(&e, &id);
+        get_balance(&e, &id)
+    }
+
+    fn transfer(e: Env, from: Address, to: Address, amount: i128) {
+        from.require_auth();
+        check_amount(&e, &amount);
+
+        spend_balance(&e, &from, &amount);
+        receive_balance(&e, &to, &amount);
+        pump_core(&e);
+
+        e.events()
+            .publish((symbol_short!("transfer"), from, to), amount);
+    }
+
+    fn transfer_from(e: Env, spender: Address, from: Address, to: Address, amount: i128) {
+        spender.require_auth();
+        check_amount(&e, &amount);
+
+        spend_allowance(&e, &from, &spender, &amount);
+        spend_balance(&e, &from, &amount);
+        receive_balance(&e, &to, &amount);
+        pump_core(&e);
+
+        e.events()
+            .publish((symbol_short!("transfer"), from, to), amount);
+    }
+
+    fn burn(e: Env, from: Address, amount: i128) {
+        from.require_auth();
+        check_amount(&e, &amount);
+
+        spend_balance(&e, &from, &amount);
+        pump_core(&e);
+
+        e.events().publish((symbol_short!("burn"), from), amount);
+    }
+
+    fn burn_from(e: Env, spender: Address, from: Address, amount: i128) {
+        spender.require_auth();
+        check_amount(&e, &amount);
+
+        spend_allowance(&e, &from, &spender, &amount);
+        spend_balance(&e, &from, &amount);
+        pump_core(&e);
+
+        e.events().publish((symbol_short!("burn"), from), amount);
+    }
+
+    fn decimals(_e: Env) -> u32 {
+        DECIMALS
+    }
+
+    fn name(e: Env) -> String {
+        String::from_str(&e, NAME)
+    }
+
+    fn symbol(e: Env) -> String {
+        String::from_str(&e, SYMBOL)
+    }
+}
+
+pub fn check_amount(e: &Env, amount: &i128) {
+    if *amount < 0 {
+        panic_with_error!(e, &ContractErrors::NegativeAmount);
+    }
+}
+
+pub fn receive_balance(e: &Env, id: &Address, amount: &i128) {
+    set_balance(e, id, &(get_balance(e, id) + amount));
+    pump_balance(e, id);
+}
+
+pub fn spend_balance(e: &Env, id: &Address, amount: &i128) {
+    let balance: i128 = get_balance(e, id);
+    if balance < *amount {
+        panic_with_error!(e, &ContractErrors::InsufficientBalance);
+    }
+
+    set_balance(e, id, &(balance - amount));
+    pump_balance(e, id);
+}
+
+pub fn spend_allowance(e: &Env, from: &Address, spender: &Address, amount: &i128) {
+    let allowance: AllowanceValue = get_allowance(e, from, spender);
+    if allowance.amount < *amount {
+        panic_with_error!(e, &ContractErrors::InsufficientAllowance);
+    }
+
+    if *amount > 0 {
+        set_allowance(
+            e,
+            from,
+            spender,
+            &AllowanceValue {
+                amount: allowance.amount - amount,
+                expiration_ledger: allowance.expiration_ledger,
+            },
+        );
+    }
+}
@@ -0,0 +1,13 @@
+use soroban_sdk::contracterror;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+pub enum ContractErrors {
+    AlreadyInitialized = 0,
+    NotInitialized = 1,
+    NegativeAmount = 2,
+    InsufficientBalance = 3,
+    InsufficientAllowance = 4,
+    InvalidExpirationLedger = 5,
+    NotTheDeployer = 6,
+}
@@ -0,0 +1,6 @@
+#![no_std]
+
+pub mod contract;
+pub mod errors;
+pub mod storage;
+mod tests;
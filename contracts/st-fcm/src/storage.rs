@@ -0,0 +1,84 @@
+use soroban_sdk::{contracttype, Address, Env};
+
+const DAY_LEDGER: u32 = 17280;
+
+#[contracttype]
+pub struct AllowanceValue {
+    pub amount: i128,
+    pub expiration_ledger: u32,
+}
+
+#[contracttype]
+pub enum StorageKeys {
+    // The reactor, it's the only one that can mint and redeem receipts
+    Admin,
+    Balance(Address),
+
+    // Allowances are temporal, they are dropped once they expire
+    Allowance((Address, Address)),
+}
+
+pub fn pump_core(e: &Env) {
+    e.storage()
+        .instance()
+        .extend_ttl(DAY_LEDGER, DAY_LEDGER * 3);
+}
+
+pub fn set_admin(e: &Env, admin: &Address) {
+    e.storage().instance().set(&StorageKeys::Admin, admin);
+}
+
+pub fn get_admin(e: &Env) -> Option<Address> {
+    e.storage().instance().get(&StorageKeys::Admin)
+}
+
+pub fn get_balance(e: &Env, id: &Address) -> i128 {
+    e.storage()
+        .persistent()
+        .get(&StorageKeys::Balance(id.clone()))
+        .unwrap_or(0)
+}
+
+pub fn set_balance(e: &Env, id: &Address, amount: &i128) {
+    e.storage()
+        .persistent()
+        .set(&StorageKeys::Balance(id.clone()), amount);
+}
+
+pub fn pump_balance(e: &Env, id: &Address) {
+    if e.storage()
+        .persistent()
+        .has(&StorageKeys::Balance(id.clone()))
+    {
+        e.storage().persistent().extend_ttl(
+            &StorageKeys::Balance(id.clone()),
+            DAY_LEDGER * 15,
+            DAY_LEDGER * 30,
+        );
+    }
+}
+
+pub fn get_allowance(e: &Env, from: &Address, spender: &Address) -> AllowanceValue {
+    let allowance: Option<AllowanceValue> = e
+        .storage()
+        .temporary()
+        .get(&StorageKeys::Allowance((from.clone(), spender.clone())));
+
+    match allowance {
+        Some(allowance) if allowance.expiration_ledger >= e.ledger().sequence() => allowance,
+        _ => AllowanceValue {
+            amount: 0,
+            expiration_ledger: 0,
+        },
+    }
+}
+
+pub fn set_allowance(e: &Env, from: &Address, spender: &Address, allowance: &AllowanceValue) {
+    let key: StorageKeys = StorageKeys::Allowance((from.clone(), spender.clone()));
+    e.storage().temporary().set(&key, allowance);
+
+    if allowance.amount > 0 {
+        let live_for: u32 = allowance.expiration_ledger - e.ledger().sequence();
+        e.storage().temporary().extend_ttl(&key, live_for, live_for);
+    }
+}
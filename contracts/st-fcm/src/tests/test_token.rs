@@ -0,0 +1,82 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::{token, Address, BytesN, Env, String};
+
+use crate::contract::{StakedFcmContract, StakedFcmContractClient};
+use crate::errors::ContractErrors;
+
+#[test]
+fn test_receipts_are_transferable() {
+    let e: Env = Env::default();
+    let admin: Address = Address::generate(&e);
+    let holder: Address = Address::generate(&e);
+    let buyer: Address = Address::generate(&e);
+    let spender: Address = Address::generate(&e);
+
+    let deployer: Address = Address::generate(&e);
+    let salt: BytesN<32> = BytesN::from_array(&e, &[1u8; 32]);
+    let deployed_address: Address = e
+        .deployer()
+        .with_address(deployer.clone(), salt.clone())
+        .deployed_address();
+    let contract_id: Address = e.register_contract(&deployed_address, StakedFcmContract);
+    let contract_client = StakedFcmContractClient::new(&e, &contract_id);
+    let token_client = token::Client::new(&e, &contract_id);
+
+    // Someone watching the deployment can't take the admin first
+    assert!(contract_client
+        .try_initialize(&deployer, &salt, &holder)
+        .is_err());
+    assert_eq!(
+        contract_client
+            .mock_all_auths()
+            .try_initialize(&holder, &salt, &holder)
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::NotTheDeployer.into()
+    );
+
+    contract_client
+        .mock_all_auths()
+        .initialize(&deployer, &salt, &admin);
+    assert_eq!(
+        contract_client
+            .mock_all_auths()
+            .try_initialize(&deployer, &salt, &holder)
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::AlreadyInitialized.into()
+    );
+    assert_eq!(contract_client.admin(), admin);
+    assert_eq!(token_client.symbol(), String::from_str(&e, "stFCM"));
+    assert_eq!(token_client.decimals(), 7);
+
+    assert!(contract_client.try_mint(&holder, &100).is_err());
+    contract_client.mock_all_auths().mint(&holder, &100);
+
+    token_client.mock_all_auths().transfer(&holder, &buyer, &40);
+    assert_eq!(token_client.balance(&holder), 60);
+    assert_eq!(token_client.balance(&buyer), 40);
+
+    token_client
+        .mock_all_auths()
+        .approve(&buyer, &spender, &30, &1000);
+    token_client
+        .mock_all_auths()
+        .transfer_from(&spender, &buyer, &holder, &20);
+    assert_eq!(token_client.allowance(&buyer, &spender), 10);
+    assert_eq!(token_client.balance(&holder), 80);
+
+    contract_client.mock_all_auths().clawback(&holder, &80);
+    assert_eq!(token_client.balance(&holder), 0);
+
+    assert_eq!(
+        contract_client
+            .mock_all_auths()
+            .try_clawback(&buyer, &21)
+            .unwrap_err()
+            .unwrap(),
+        ContractErrors::InsufficientBalance.into()
+    );
+}